@@ -0,0 +1,157 @@
+use std::{
+    io::{self, Write},
+    thread,
+    time::Duration,
+};
+
+use crate::frames::{self, Format, Frame};
+use crate::{day23, stream, Error};
+
+/// A simulation as text screens to show one after another, and a line to finish on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Playback {
+    pub screens: Vec<String>,
+    pub summary: String,
+}
+
+/// One screen per generation, holding every frame (every slice, for day 17) of it.
+fn from_frames(frames: &[Frame]) -> Playback {
+    let mut screens: Vec<String> = vec![];
+    for frame in frames {
+        if screens.len() <= frame.generation {
+            screens.push(String::new());
+        } else {
+            screens[frame.generation].push('\n');
+        }
+
+        let screen = &mut screens[frame.generation];
+        screen.push_str(&frame.name);
+        screen.push('\n');
+        screen.push_str(&Format::Ascii.render(frame));
+    }
+
+    let last = frames.last().map_or(0, |frame| frame.generation);
+    let live: usize = frames
+        .iter()
+        .filter(|frame| frame.generation == last)
+        .map(Frame::live)
+        .sum();
+
+    Playback {
+        screens,
+        summary: format!("{} generations, {} live at the end", last, live),
+    }
+}
+
+/// Plays back `day`'s simulation: the automata of days 11, 17 and 24 as in
+/// [`frames::frames`], and the first `generations` (100 by default) moves of the day 23
+/// part 1 cup circle.
+pub fn playback(
+    day: u8,
+    part: u8,
+    input: &str,
+    generations: Option<usize>,
+) -> Result<Playback, Error> {
+    let input = &stream::normalize(input);
+    match (day, part) {
+        (23, 1) => {
+            let cups = day23::input_generator(input)?;
+            let moves = day23::moves(&cups, generations.unwrap_or(100));
+
+            Ok(Playback {
+                screens: moves
+                    .iter()
+                    .enumerate()
+                    .map(|(i, cups)| format!("after {} moves\ncups: {}\n", i, cups))
+                    .collect(),
+                summary: format!(
+                    "{} moves, ending at {}",
+                    moves.len() - 1,
+                    moves[moves.len() - 1]
+                ),
+            })
+        }
+        (23, _) => Err(Error::NoFrames(day)),
+        _ => frames::frames(day, part, input, generations).map(|frames| from_frames(&frames)),
+    }
+}
+
+/// Shows each screen in turn. With `ansi`, every screen is redrawn in place, `delay`
+/// apart; otherwise they are written out one after another, separated by blank lines.
+pub fn play(
+    out: &mut impl Write,
+    playback: &Playback,
+    delay: Duration,
+    ansi: bool,
+) -> io::Result<()> {
+    if ansi {
+        write!(out, "\x1b[2J")?;
+    }
+
+    for (i, screen) in playback.screens.iter().enumerate() {
+        if ansi {
+            if i > 0 {
+                thread::sleep(delay);
+            }
+            // home the cursor and overwrite, clearing whatever the last screen left behind
+            write!(out, "\x1b[H")?;
+            for line in screen.lines() {
+                writeln!(out, "{}\x1b[K", line)?;
+            }
+            write!(out, "\x1b[J")?;
+            out.flush()?;
+        } else {
+            if i > 0 {
+                writeln!(out)?;
+            }
+            write!(out, "{}", screen)?;
+        }
+    }
+
+    writeln!(out, "{}", playback.summary)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_playback() {
+        let seats = playback(11, 1, "L.L\nLLL\nL.L", None).unwrap();
+        assert_eq!(seats.screens.len(), 3);
+        assert_eq!(seats.screens[1], "gen001\n#.#\n###\n#.#\n");
+        assert_eq!(seats.summary, "2 generations, 6 live at the end");
+
+        let cubes = playback(17, 1, ".#.\n..#\n###", Some(1)).unwrap();
+        assert_eq!(cubes.screens.len(), 2);
+        assert!(cubes.screens[1].starts_with("gen001-z-1\n"));
+        assert_eq!(cubes.summary, "1 generations, 11 live at the end");
+
+        let cups = playback(23, 1, "389125467", Some(10)).unwrap();
+        assert_eq!(
+            cups.screens[0],
+            "after 0 moves\ncups: (3) 8 9 1 2 5 4 6 7\n"
+        );
+        assert_eq!(cups.summary, "10 moves, ending at (8) 3 7 4 1 9 2 6 5");
+        assert_eq!(playback(23, 2, "389125467", None), Err(Error::NoFrames(23)));
+    }
+
+    #[test]
+    fn test_play() {
+        let playback = Playback {
+            screens: vec!["a\nb\n".to_owned(), "c\n".to_owned()],
+            summary: "done".to_owned(),
+        };
+
+        let mut plain = vec![];
+        play(&mut plain, &playback, Duration::from_secs(0), false).unwrap();
+        assert_eq!(String::from_utf8(plain).unwrap(), "a\nb\n\nc\ndone\n");
+
+        let mut ansi = vec![];
+        play(&mut ansi, &playback, Duration::from_secs(0), true).unwrap();
+        assert_eq!(
+            String::from_utf8(ansi).unwrap(),
+            "\x1b[2J\x1b[Ha\x1b[K\nb\x1b[K\n\x1b[J\x1b[Hc\x1b[K\n\x1b[Jdone\n"
+        );
+    }
+}
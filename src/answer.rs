@@ -0,0 +1,141 @@
+use std::{convert::TryFrom, fmt::Display};
+
+use crate::bigint::BigUint;
+
+#[derive(Debug, Clone, Eq)]
+pub enum Answer {
+    Integer(i64),
+    BigInteger(i128),
+    /// Too wide for an `i128`, from the days that count in [`BigUint`].
+    Unbounded(BigUint),
+    String(String),
+    Structured(Vec<(String, Answer)>),
+}
+
+impl Answer {
+    pub fn structured<K: Into<String>, V: Into<Answer>>(
+        fields: impl IntoIterator<Item = (K, V)>,
+    ) -> Self {
+        Answer::Structured(
+            fields
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        )
+    }
+
+    /// Looks up a field of a structured answer.
+    pub fn field(&self, name: &str) -> Option<&Answer> {
+        match self {
+            Answer::Structured(fields) => fields.iter().find(|(k, _)| k == name).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_i128(&self) -> Option<i128> {
+        match self {
+            Answer::Integer(v) => Some(*v as i128),
+            Answer::BigInteger(v) => Some(*v),
+            Answer::Unbounded(v) => v.to_u128().and_then(|v| i128::try_from(v).ok()),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for Answer {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Answer::String(a), Answer::String(b)) => a == b,
+            (Answer::Structured(a), Answer::Structured(b)) => a == b,
+            (Answer::Unbounded(a), Answer::Unbounded(b)) => a == b,
+            _ => match (self.as_i128(), other.as_i128()) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            },
+        }
+    }
+}
+
+impl PartialEq<i64> for Answer {
+    fn eq(&self, other: &i64) -> bool {
+        self.as_i128() == Some(*other as i128)
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        matches!(self, Answer::String(s) if s == other)
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Integer(v) => write!(f, "{}", v),
+            Answer::BigInteger(v) => write!(f, "{}", v),
+            Answer::Unbounded(v) => write!(f, "{}", v),
+            Answer::String(s) => f.write_str(s),
+            Answer::Structured(fields) => {
+                for (i, (k, v)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", k, v)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+macro_rules! from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(v: $t) -> Self {
+                    match i64::try_from(v) {
+                        Ok(v) => Answer::Integer(v),
+                        Err(_) => Answer::BigInteger(v as i128),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+from_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, i128);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::String(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::String(s.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_equality() {
+        assert_eq!(Answer::from(5u8), Answer::BigInteger(5));
+        assert_eq!(Answer::from(u64::MAX), Answer::BigInteger(u64::MAX as i128));
+        assert_ne!(Answer::from(5), Answer::from("5"));
+        assert_eq!(Answer::Unbounded(BigUint::from(5)), Answer::from(5u8));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(-3).to_string(), "-3");
+        assert_eq!(Answer::from(i128::MAX).to_string(), i128::MAX.to_string());
+        assert_eq!(
+            Answer::structured(vec![("index", 9), ("acc", 8)]).to_string(),
+            "index: 9, acc: 8"
+        );
+    }
+}
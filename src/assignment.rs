@@ -0,0 +1,207 @@
+//! Matching keys to values when each key has a set of candidates and no two keys can share a
+//! value: day 16's fields and ticket columns, and day 21's allergens and ingredients.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt::{Debug, Display},
+    hash::Hash,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssignmentError<K> {
+    /// No way of giving every key a candidate of its own.
+    Contradiction,
+    /// More than one way; these keys get different values in two of them.
+    Ambiguous(Vec<K>),
+}
+
+impl<K: Debug> Display for AssignmentError<K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssignmentError::Contradiction => {
+                write!(f, "no assignment gives every key a value of its own")
+            }
+            AssignmentError::Ambiguous(keys) => {
+                write!(f, "more than one assignment fits, differing on {:?}", keys)
+            }
+        }
+    }
+}
+
+/// Takes each value that is some key's only candidate away from every other key, until
+/// nothing changes. False if that leaves a key with no candidates at all.
+fn eliminate<V: Ord + Clone>(sets: &mut [BTreeSet<V>]) -> bool {
+    let mut settled = vec![false; sets.len()];
+    loop {
+        let single = (0..sets.len()).find(|&i| !settled[i] && sets[i].len() == 1);
+        let i = match single {
+            Some(i) => i,
+            None => return sets.iter().all(|set| !set.is_empty()),
+        };
+
+        settled[i] = true;
+        let value = sets[i].iter().next().unwrap().clone();
+        for (j, set) in sets.iter_mut().enumerate() {
+            if j != i && set.remove(&value) && set.is_empty() {
+                return false;
+            }
+        }
+    }
+}
+
+/// Collects up to `limit` assignments, eliminating singles and then trying each candidate of
+/// the key with the fewest left.
+fn search<V: Ord + Clone>(mut sets: Vec<BTreeSet<V>>, limit: usize, out: &mut Vec<Vec<V>>) {
+    if !eliminate(&mut sets) {
+        return;
+    }
+
+    let open = (0..sets.len())
+        .filter(|&i| sets[i].len() > 1)
+        .min_by_key(|&i| sets[i].len());
+    let i = match open {
+        Some(i) => i,
+        None => {
+            out.push(
+                sets.into_iter()
+                    .map(|set| set.into_iter().next().unwrap())
+                    .collect(),
+            );
+            return;
+        }
+    };
+
+    for value in sets[i].clone() {
+        if out.len() == limit {
+            return;
+        }
+        let mut next = sets.clone();
+        next[i] = BTreeSet::from([value]);
+        search(next, limit, out);
+    }
+}
+
+fn assignments<K, V>(candidates: &HashMap<K, HashSet<V>>, limit: usize) -> Vec<BTreeMap<K, V>>
+where
+    K: Hash + Ord + Clone,
+    V: Ord + Clone,
+{
+    let mut keys = candidates.keys().cloned().collect::<Vec<_>>();
+    keys.sort();
+    let sets = keys
+        .iter()
+        .map(|key| candidates[key].iter().cloned().collect())
+        .collect();
+
+    let mut out = vec![];
+    search(sets, limit, &mut out);
+    out.into_iter()
+        .map(|values| keys.iter().cloned().zip(values).collect())
+        .collect()
+}
+
+/// Every way of giving each key one of its candidates, with no value used twice.
+pub fn all<K, V>(candidates: &HashMap<K, HashSet<V>>) -> Vec<BTreeMap<K, V>>
+where
+    K: Hash + Ord + Clone,
+    V: Ord + Clone,
+{
+    assignments(candidates, usize::MAX)
+}
+
+/// The only way of giving each key one of its candidates, with no value used twice.
+pub fn solve<K, V>(
+    candidates: &HashMap<K, HashSet<V>>,
+) -> Result<BTreeMap<K, V>, AssignmentError<K>>
+where
+    K: Hash + Ord + Clone,
+    V: Ord + Clone,
+{
+    let mut found = assignments(candidates, 2);
+    match found.len() {
+        0 => Err(AssignmentError::Contradiction),
+        1 => Ok(found.remove(0)),
+        _ => Err(AssignmentError::Ambiguous(
+            found[0]
+                .iter()
+                .filter(|(key, value)| found[1][*key] != **value)
+                .map(|(key, _)| key.clone())
+                .collect(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn candidates(sets: &[(char, &[u8])]) -> HashMap<char, HashSet<u8>> {
+        sets.iter()
+            .map(|(key, values)| (*key, values.iter().copied().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn test_solve() {
+        // a strict chain of candidate sets
+        let chain = candidates(&[('a', &[0, 1, 2]), ('b', &[1]), ('c', &[1, 2])]);
+        assert_eq!(
+            solve(&chain),
+            Ok(BTreeMap::from([('a', 0), ('b', 1), ('c', 2)]))
+        );
+
+        // two singles at once, leaving the rest to a key that isn't one smaller
+        let singles = candidates(&[('a', &[0]), ('b', &[1]), ('c', &[0, 1, 2])]);
+        assert_eq!(
+            solve(&singles),
+            Ok(BTreeMap::from([('a', 0), ('b', 1), ('c', 2)]))
+        );
+
+        // no singles to start from, and two ways through
+        let cycle = candidates(&[
+            ('a', &[0, 1]),
+            ('b', &[1, 2]),
+            ('c', &[0, 2]),
+            ('d', &[2, 3]),
+        ]);
+        assert_eq!(
+            solve(&cycle),
+            Err(AssignmentError::Ambiguous(vec!['a', 'b', 'c']))
+        );
+
+        let ambiguous = candidates(&[('a', &[0, 1]), ('b', &[0, 1]), ('c', &[2])]);
+        assert_eq!(
+            solve(&ambiguous),
+            Err(AssignmentError::Ambiguous(vec!['a', 'b']))
+        );
+        assert_eq!(
+            solve(&candidates(&[('a', &[0]), ('b', &[0]), ('c', &[0, 1])])),
+            Err(AssignmentError::Contradiction)
+        );
+        assert_eq!(
+            solve(&candidates(&[('a', &[])])),
+            Err(AssignmentError::Contradiction)
+        );
+        assert_eq!(
+            AssignmentError::Ambiguous(vec!['a', 'b']).to_string(),
+            "more than one assignment fits, differing on ['a', 'b']"
+        );
+    }
+
+    #[test]
+    fn test_all() {
+        let open = candidates(&[('a', &[0, 1, 2]), ('b', &[0, 1, 2]), ('c', &[0, 1, 2])]);
+        assert_eq!(all(&open).len(), 6);
+
+        let ambiguous = candidates(&[('a', &[0, 1]), ('b', &[0, 1]), ('c', &[2])]);
+        assert_eq!(
+            all(&ambiguous),
+            vec![
+                BTreeMap::from([('a', 0), ('b', 1), ('c', 2)]),
+                BTreeMap::from([('a', 1), ('b', 0), ('c', 2)])
+            ]
+        );
+        assert!(all(&candidates(&[('a', &[0]), ('b', &[0])])).is_empty());
+        assert_eq!(all::<char, u8>(&HashMap::new()), vec![BTreeMap::new()]);
+    }
+}
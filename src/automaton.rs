@@ -0,0 +1,270 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use crate::grid::{Grid, Pos, DIRECTIONS8};
+
+/// How cells are connected.
+pub trait Topology {
+    type Cell: Copy + Eq + Hash;
+
+    fn neighbours(&self, cell: Self::Cell) -> impl Iterator<Item = Self::Cell> + '_;
+
+    /// Every cell, for bounded topologies. Unbounded ones return `None`, and only cells
+    /// next to a live one are considered for birth.
+    fn cells(&self) -> Option<&[Self::Cell]> {
+        None
+    }
+}
+
+/// Whether a cell is alive in the next generation, given whether it is alive now and
+/// how many of its neighbours are.
+pub trait Rule {
+    fn next(&self, alive: bool, neighbours: usize) -> bool;
+}
+
+impl<F: Fn(bool, usize) -> bool> Rule for F {
+    fn next(&self, alive: bool, neighbours: usize) -> bool {
+        self(alive, neighbours)
+    }
+}
+
+/// The usual `B../S..` life-like rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BirthSurvive {
+    pub birth: &'static [usize],
+    pub survive: &'static [usize],
+}
+
+impl Rule for BirthSurvive {
+    fn next(&self, alive: bool, neighbours: usize) -> bool {
+        if alive {
+            self.survive.contains(&neighbours)
+        } else {
+            self.birth.contains(&neighbours)
+        }
+    }
+}
+
+/// An unbounded lattice in `N` dimensions where every cell touches the `3^N - 1` cells
+/// around it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lattice<const N: usize>;
+
+impl<const N: usize> Topology for Lattice<N> {
+    type Cell = [i32; N];
+
+    fn neighbours(&self, cell: [i32; N]) -> impl Iterator<Item = [i32; N]> + '_ {
+        let count = 3usize.pow(N as u32);
+        (0..count)
+            .filter(move |&i| i != count / 2)
+            .map(move |mut i| {
+                let mut out = cell;
+                for c in out.iter_mut() {
+                    *c += (i % 3) as i32 - 1;
+                    i /= 3;
+                }
+                out
+            })
+    }
+}
+
+/// Axial `(x, y)` steps to the six neighbours of a hex: east, southeast, southwest, west,
+/// northwest and northeast.
+pub const HEX_DIRECTIONS: [(i32, i32); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+/// An unbounded hex grid in axial coordinates.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Hex;
+
+impl Topology for Hex {
+    type Cell = (i32, i32);
+
+    fn neighbours(&self, (x, y): (i32, i32)) -> impl Iterator<Item = (i32, i32)> + '_ {
+        HEX_DIRECTIONS.iter().map(move |(dx, dy)| (x + dx, y + dy))
+    }
+}
+
+/// A bounded square grid in which only the open cells take part.
+#[derive(Debug, Clone)]
+pub struct SquareGrid {
+    cells: Vec<Pos>,
+    neighbours: Grid<Vec<Pos>>,
+}
+
+impl SquareGrid {
+    /// Each open cell touches the open cells among its eight neighbours.
+    pub fn adjacent(open: &Grid<bool>) -> Self {
+        SquareGrid::build(open, |pos| {
+            open.neighbours8(pos).filter(|&pos| open[pos]).collect()
+        })
+    }
+
+    /// Each open cell touches the nearest open cell in each of the eight directions.
+    pub fn line_of_sight(open: &Grid<bool>) -> Self {
+        SquareGrid::build(open, |pos| {
+            DIRECTIONS8
+                .iter()
+                .filter_map(|&step| open.ray(pos, step).find(|&pos| open[pos]))
+                .collect()
+        })
+    }
+
+    fn build(open: &Grid<bool>, mut links: impl FnMut(Pos) -> Vec<Pos>) -> Self {
+        SquareGrid {
+            cells: open.positions().filter(|&pos| open[pos]).collect(),
+            neighbours: Grid::from_fn(open.height(), open.width(), |pos| {
+                if open[pos] {
+                    links(pos)
+                } else {
+                    vec![]
+                }
+            }),
+        }
+    }
+}
+
+impl Topology for SquareGrid {
+    type Cell = Pos;
+
+    fn neighbours(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        self.neighbours[pos].iter().copied()
+    }
+
+    fn cells(&self) -> Option<&[Pos]> {
+        Some(&self.cells)
+    }
+}
+
+/// A set of live cells evolving under `rule` on `topology`.
+#[derive(Debug, Clone)]
+pub struct Automaton<T: Topology, R> {
+    topology: T,
+    rule: R,
+    live: HashSet<T::Cell>,
+    generation: usize,
+}
+
+impl<T: Topology, R: Rule> Automaton<T, R> {
+    pub fn new(topology: T, rule: R, live: impl IntoIterator<Item = T::Cell>) -> Self {
+        Automaton {
+            topology,
+            rule,
+            live: live.into_iter().collect(),
+            generation: 0,
+        }
+    }
+
+    pub fn live(&self) -> &HashSet<T::Cell> {
+        &self.live
+    }
+
+    pub fn is_alive(&self, cell: T::Cell) -> bool {
+        self.live.contains(&cell)
+    }
+
+    pub fn population(&self) -> usize {
+        self.live.len()
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Advances one generation, returning whether anything changed.
+    pub fn step(&mut self) -> bool {
+        let mut counts: HashMap<T::Cell, usize> = HashMap::new();
+        for &cell in &self.live {
+            for neighbour in self.topology.neighbours(cell) {
+                *counts.entry(neighbour).or_insert(0) += 1;
+            }
+        }
+
+        let count = |cell: &T::Cell| counts.get(cell).copied().unwrap_or(0);
+        let next: HashSet<_> = match self.topology.cells() {
+            Some(cells) => cells
+                .iter()
+                .filter(|cell| self.rule.next(self.live.contains(cell), count(cell)))
+                .copied()
+                .collect(),
+            None => counts
+                .keys()
+                .chain(&self.live)
+                .filter(|cell| self.rule.next(self.live.contains(cell), count(cell)))
+                .copied()
+                .collect(),
+        };
+
+        self.generation += 1;
+        let changed = next != self.live;
+        self.live = next;
+        changed
+    }
+
+    pub fn run(&mut self, generations: usize) -> &mut Self {
+        for _ in 0..generations {
+            self.step();
+        }
+        self
+    }
+
+    /// Steps until a generation leaves the live cells unchanged. Never returns for a
+    /// pattern that keeps moving or oscillating.
+    pub fn run_until_stable(&mut self) -> &mut Self {
+        while self.step() {}
+        self
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LIFE: BirthSurvive = BirthSurvive {
+        birth: &[3],
+        survive: &[2, 3],
+    };
+
+    #[test]
+    fn test_lattice() {
+        assert_eq!(Lattice::<3>.neighbours([0, 0, 0]).count(), 26);
+        assert!(!Lattice::<2>.neighbours([4, 4]).any(|cell| cell == [4, 4]));
+
+        let mut blinker = Automaton::new(Lattice::<2>, LIFE, vec![[0, -1], [0, 0], [0, 1]]);
+        blinker.step();
+        assert!(blinker.is_alive([1, 0]) && !blinker.is_alive([0, 1]));
+        blinker.run(3);
+        assert_eq!((blinker.generation(), blinker.population()), (4, 3));
+        assert!(blinker.is_alive([0, 1]));
+
+        let mut block = Automaton::new(Lattice::<2>, LIFE, vec![[0, 0], [0, 1], [1, 0], [1, 1]]);
+        assert_eq!(block.run_until_stable().generation(), 1);
+    }
+
+    #[test]
+    fn test_square_grid() {
+        let open = Grid::parse("#.#\n...\n#.#", |c| Some(c == '#'), "").unwrap();
+        assert_eq!(SquareGrid::adjacent(&open).neighbours((0, 0)).count(), 0);
+        assert_eq!(
+            SquareGrid::line_of_sight(&open).neighbours((0, 0)).count(),
+            3
+        );
+
+        let mut corners = Automaton::new(
+            SquareGrid::line_of_sight(&open),
+            |_, neighbours| neighbours == 0,
+            vec![],
+        );
+        corners.step();
+        assert_eq!(corners.population(), 4);
+    }
+
+    #[test]
+    fn test_hex() {
+        let mut flower = Automaton::new(Hex, |alive, n| alive || n == 6, Hex.neighbours((0, 0)));
+        flower.step();
+        assert_eq!(flower.population(), 7);
+        assert!(!flower.step());
+    }
+}
@@ -0,0 +1,238 @@
+use std::{
+    any::Any,
+    fmt::Write,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{solve, Answer, Error};
+
+/// One part of one day, with the input to solve it for. Parts of the same day can share
+/// their input.
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub day: u8,
+    pub part: u8,
+    pub input: Arc<str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Solved(Answer),
+    Failed(Error),
+    Panicked(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub day: u8,
+    pub part: u8,
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        matches!(self.outcome, Outcome::Solved(_))
+    }
+}
+
+/// Every job's report, in the order the jobs were given, and how long they took together.
+#[derive(Debug, Clone)]
+pub struct Batch {
+    pub reports: Vec<Report>,
+    pub threads: usize,
+    pub wall: Duration,
+}
+
+/// The message a panic was raised with, as `catch_unwind` hands it back.
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => (*message).to_owned(),
+            Err(_) => "no message".to_owned(),
+        },
+    }
+}
+
+fn run_job(job: &Job) -> Report {
+    let start = Instant::now();
+    let outcome =
+        match panic::catch_unwind(AssertUnwindSafe(|| solve(job.day, job.part, &job.input))) {
+            Ok(Ok(answer)) => Outcome::Solved(answer),
+            Ok(Err(err)) => Outcome::Failed(err),
+            Err(payload) => Outcome::Panicked(panic_message(payload)),
+        };
+
+    Report {
+        day: job.day,
+        part: job.part,
+        outcome,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Solves `jobs` on up to `threads` worker threads, each taking the next unstarted job
+/// until none are left. A job that panics is reported as such rather than taking the
+/// batch down, though the panic message is still printed as usual.
+pub fn run(jobs: &[Job], threads: usize) -> Batch {
+    let threads = threads.clamp(1, jobs.len().max(1));
+    let start = Instant::now();
+    let next = AtomicUsize::new(0);
+    let reports = Mutex::new((0..jobs.len()).map(|_| None).collect::<Vec<_>>());
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let job = match jobs.get(i) {
+                    Some(job) => job,
+                    None => break,
+                };
+                let report = run_job(job);
+                reports.lock().unwrap()[i] = Some(report);
+            });
+        }
+    });
+
+    Batch {
+        reports: reports
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|report| report.unwrap())
+            .collect(),
+        threads,
+        wall: start.elapsed(),
+    }
+}
+
+fn millis(d: Duration) -> String {
+    format!("{:.3}", d.as_secs_f64() * 1000.0)
+}
+
+/// Renders one row per job and a closing summary.
+pub fn table(batch: &Batch) -> String {
+    let mut out = format!("{:>3}  {:>4}  {:>12}  {}\n", "day", "part", "ms", "result");
+
+    for report in &batch.reports {
+        let result = match &report.outcome {
+            Outcome::Solved(answer) => answer.to_string(),
+            Outcome::Failed(err) => format!("error: {}", err),
+            Outcome::Panicked(message) => format!("panicked: {}", message),
+        };
+        writeln!(
+            out,
+            "{:>3}  {:>4}  {:>12}  {}",
+            report.day,
+            report.part,
+            millis(report.elapsed),
+            result
+        )
+        .unwrap();
+    }
+
+    let failed = batch.reports.iter().filter(|r| !r.passed()).count();
+    let busy: Duration = batch.reports.iter().map(|r| r.elapsed).sum();
+    writeln!(
+        out,
+        "{} jobs on {} threads, {} failed: {} ms wall, {} ms busy",
+        batch.reports.len(),
+        batch.threads,
+        failed,
+        millis(batch.wall),
+        millis(busy)
+    )
+    .unwrap();
+
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ParseError;
+
+    fn job(day: u8, part: u8, input: &str) -> Job {
+        Job {
+            day,
+            part,
+            input: input.into(),
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let jobs = vec![
+            job(25, 1, "5764801\n17807724"),
+            job(1, 2, "1721\n979\n366\n299\n675\n1456"),
+            job(1, 1, "12\nabc"),
+            job(26, 1, ""),
+            // too short for a window that sums to the (missing) invalid number
+            job(9, 2, "1"),
+        ];
+
+        let batch = run(&jobs, 3);
+        assert_eq!(batch.threads, 3);
+        assert_eq!(
+            batch
+                .reports
+                .iter()
+                .map(|report| (report.day, report.part, report.outcome.clone()))
+                .collect::<Vec<_>>(),
+            [
+                (25, 1, Outcome::Solved(14897079.into())),
+                (1, 2, Outcome::Solved(241861950.into())),
+                (
+                    1,
+                    1,
+                    Outcome::Failed(Error::Parse(ParseError::new(2, 1, "an expense amount")))
+                ),
+                (26, 1, Outcome::Failed(Error::UnknownDay(26))),
+                (
+                    9,
+                    2,
+                    Outcome::Panicked("no range of at least two numbers sums to 0".to_owned())
+                ),
+            ]
+        );
+
+        assert_eq!(run(&jobs[..1], 8).threads, 1);
+    }
+
+    #[test]
+    fn test_table() {
+        let batch = Batch {
+            reports: vec![
+                Report {
+                    day: 1,
+                    part: 1,
+                    outcome: Outcome::Solved(514579.into()),
+                    elapsed: Duration::from_millis(2),
+                },
+                Report {
+                    day: 9,
+                    part: 2,
+                    outcome: Outcome::Panicked("oops".to_owned()),
+                    elapsed: Duration::from_millis(1),
+                },
+            ],
+            threads: 2,
+            wall: Duration::from_millis(2),
+        };
+
+        assert_eq!(
+            table(&batch),
+            "day  part            ms  result\n  \
+               1     1         2.000  514579\n  \
+               9     2         1.000  panicked: oops\n\
+             2 jobs on 2 threads, 1 failed: 2.000 ms wall, 3.000 ms busy\n"
+        );
+    }
+}
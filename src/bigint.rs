@@ -0,0 +1,316 @@
+//! Unsigned integers as wide as they need to be, for the answers that can outgrow a `u64`
+//! on large inputs: day 10's arrangements, day 13's timestamp, day 18's sums and day 23's
+//! product. Those days count in [`Exact`], which is [`BigUint`] with the `exact` feature.
+
+use std::{
+    cmp::Ordering,
+    convert::TryFrom,
+    fmt::{Debug, Display},
+    ops::{Add, Mul, Sub},
+};
+
+use crate::checked::Integer;
+use crate::Answer;
+
+/// An arbitrary-precision unsigned integer, as base 2^32 digits, least significant first,
+/// with no leading zero digits; zero has none at all.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    digits: Vec<u32>,
+}
+
+impl BigUint {
+    fn normalize(mut self) -> Self {
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+        self
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// The remainder on dividing by `m`.
+    pub fn rem_u64(&self, m: u64) -> u64 {
+        self.digits.iter().rev().fold(0, |rem, &digit| {
+            (((rem as u128) << 32 | digit as u128) % m as u128) as u64
+        })
+    }
+
+    /// Divides in place by `d`, returning the remainder.
+    fn div_rem_u32(&mut self, d: u32) -> u32 {
+        let mut rem = 0u64;
+        for digit in self.digits.iter_mut().rev() {
+            let n = rem << 32 | *digit as u64;
+            *digit = (n / d as u64) as u32;
+            rem = n % d as u64;
+        }
+        *self = std::mem::take(self).normalize();
+        rem as u32
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.digits.len() > 4 {
+            return None;
+        }
+        Some(
+            self.digits
+                .iter()
+                .rev()
+                .fold(0, |n, &digit| n << 32 | digit as u128),
+        )
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> Self {
+        BigUint {
+            digits: vec![n as u32, (n >> 32) as u32],
+        }
+        .normalize()
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.digits
+            .len()
+            .cmp(&other.digits.len())
+            .then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for BigUint {
+    type Output = BigUint;
+
+    fn add(self, rhs: Self) -> BigUint {
+        let (mut long, short) = if self.digits.len() >= rhs.digits.len() {
+            (self, rhs)
+        } else {
+            (rhs, self)
+        };
+
+        let mut carry = 0u64;
+        for i in 0..long.digits.len() {
+            let sum = long.digits[i] as u64 + *short.digits.get(i).unwrap_or(&0) as u64 + carry;
+            long.digits[i] = sum as u32;
+            carry = sum >> 32;
+            if carry == 0 && i >= short.digits.len() {
+                break;
+            }
+        }
+        if carry > 0 {
+            long.digits.push(carry as u32);
+        }
+        long
+    }
+}
+
+impl BigUint {
+    /// `self - rhs`, or `None` if that would be negative.
+    pub fn checked_sub(mut self, rhs: Self) -> Option<Self> {
+        if self < rhs {
+            return None;
+        }
+
+        let mut borrow = 0i64;
+        for i in 0..self.digits.len() {
+            let mut diff = self.digits[i] as i64 - *rhs.digits.get(i).unwrap_or(&0) as i64 - borrow;
+            borrow = 0;
+            if diff < 0 {
+                diff += 1 << 32;
+                borrow = 1;
+            }
+            self.digits[i] = diff as u32;
+        }
+        Some(self.normalize())
+    }
+}
+
+impl Sub for BigUint {
+    type Output = BigUint;
+
+    fn sub(self, rhs: Self) -> BigUint {
+        self.checked_sub(rhs)
+            .expect("attempt to subtract with overflow")
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: &BigUint) -> BigUint {
+        let mut digits = vec![0u32; self.digits.len() + rhs.digits.len()];
+        for (i, &a) in self.digits.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in rhs.digits.iter().enumerate() {
+                // at most (2^32 - 1)^2 + 2 * (2^32 - 1), which still fits
+                let n = a as u64 * b as u64 + digits[i + j] as u64 + carry;
+                digits[i + j] = n as u32;
+                carry = n >> 32;
+            }
+            digits[i + rhs.digits.len()] = carry as u32;
+        }
+        BigUint { digits }.normalize()
+    }
+}
+
+impl Mul for BigUint {
+    type Output = BigUint;
+
+    fn mul(self, rhs: Self) -> BigUint {
+        &self * &rhs
+    }
+}
+
+impl Display for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_zero() {
+            return f.pad("0");
+        }
+
+        // nine decimal digits at a time, least significant first
+        let mut n = self.clone();
+        let mut chunks = vec![];
+        while !n.is_zero() {
+            chunks.push(n.div_rem_u32(1_000_000_000));
+        }
+
+        let mut s = chunks.pop().unwrap().to_string();
+        for chunk in chunks.iter().rev() {
+            s += &format!("{:09}", chunk);
+        }
+        f.pad(&s)
+    }
+}
+
+impl Debug for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl From<BigUint> for Answer {
+    fn from(n: BigUint) -> Self {
+        match n.to_u128().and_then(|n| i128::try_from(n).ok()) {
+            Some(n) => n.into(),
+            None => Answer::Unbounded(n),
+        }
+    }
+}
+
+impl Integer for BigUint {
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        Some(self + rhs)
+    }
+
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        BigUint::checked_sub(self, rhs)
+    }
+
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        Some(self * rhs)
+    }
+}
+
+/// The unsigned integers the wide-answer days count in.
+pub trait Natural: Integer + Clone + Debug + Default + Ord + From<u64> + Into<Answer> {
+    fn rem_u64(&self, m: u64) -> u64;
+}
+
+impl Natural for u64 {
+    fn rem_u64(&self, m: u64) -> u64 {
+        self % m
+    }
+}
+
+impl Natural for BigUint {
+    fn rem_u64(&self, m: u64) -> u64 {
+        BigUint::rem_u64(self, m)
+    }
+}
+
+/// What the wide-answer days count in: a [`BigUint`] with the `exact` feature, so they never
+/// wrap, and a `u64` without it.
+#[cfg(feature = "exact")]
+pub type Exact = BigUint;
+#[cfg(not(feature = "exact"))]
+pub type Exact = u64;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn pow2(n: u32) -> BigUint {
+        (0..n).fold(BigUint::from(1), |acc, _| acc.clone() + acc)
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let max = BigUint::from(u64::MAX);
+        assert_eq!((max.clone() + BigUint::from(1)).to_u128(), Some(1 << 64));
+        assert_eq!(
+            (&max * &max).to_u128(),
+            Some(u64::MAX as u128 * u64::MAX as u128)
+        );
+        assert_eq!(pow2(100) - pow2(100), BigUint::default());
+        assert_eq!(BigUint::from(3).checked_sub(BigUint::from(4)), None);
+        assert_eq!(
+            (pow2(64) - BigUint::from(1)).to_u128(),
+            Some(u64::MAX as u128)
+        );
+        assert!(pow2(65) > pow2(64) + BigUint::from(u64::MAX));
+        assert_eq!(pow2(128).to_u128(), None);
+
+        // 2^100 = 1267650600228229401496703205376
+        assert_eq!(pow2(100).rem_u64(1_000_000_007), 976371285);
+        assert_eq!(pow2(100).rem_u64(10_000_000_000), 6703205376);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(BigUint::default().to_string(), "0");
+        assert_eq!(BigUint::from(1_000_000_000).to_string(), "1000000000");
+        assert_eq!(
+            pow2(128).to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(format!("{:>4}", BigUint::from(7)), "   7");
+
+        assert_eq!(Answer::from(BigUint::from(42)), Answer::from(42));
+        assert_eq!(Answer::from(pow2(128)), Answer::Unbounded(pow2(128)));
+        assert_ne!(Answer::from(pow2(128)), Answer::from(pow2(129)));
+        assert_ne!(
+            Answer::from(pow2(128)),
+            Answer::from("340282366920938463463374607431768211456")
+        );
+        assert_eq!(
+            Answer::from(pow2(128)).to_string(),
+            "340282366920938463463374607431768211456"
+        );
+    }
+
+    #[cfg(feature = "exact")]
+    #[test]
+    fn test_exact() {
+        // every joltage from 1 to 255: a tribonacci number far past 2^64
+        let adapters: Vec<_> = (1..=255).map(|j: u32| j.to_string()).collect();
+        let answer = crate::solve(10, 2, &adapters.join("\n")).unwrap();
+        assert!(matches!(answer, Answer::Unbounded(_)));
+        assert_eq!(
+            answer.to_string(),
+            "18918711755215788870269818445686802833486224922011722784130315342464"
+        );
+        assert_eq!(
+            crate::solve(18, 2, "99999999999 * 99999999999 * 99999999999"),
+            Ok(Answer::from(999999999970000000000299999999999i128))
+        );
+    }
+}
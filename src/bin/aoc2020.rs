@@ -0,0 +1,745 @@
+use std::{
+    env, fs,
+    io::{self, BufReader, IsTerminal, Read},
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use advent_of_code2020::{
+    animate,
+    batch::{self, Job},
+    compare,
+    context::{self, Context},
+    days, examples, explain,
+    frames::{self, Format as FrameFormat},
+    generate, parts, profile, solve, solve_reader, trace,
+    verify::{self, input_path},
+    Answer, Error,
+};
+
+const USAGE: &str = "\
+usage: aoc2020 run [--day N] [--part N] [--input PATH|- | --example N] [--time]
+                   [--format plain|json] [--animate [--delay MS]] [--trace]
+                   [--explain] [--budget STEPS] [--progress]
+       aoc2020 verify [--answers PATH] [--inputs DIR]
+       aoc2020 record [--answers PATH] [--inputs DIR]
+       aoc2020 profile [--day N] [--repeat N] [--inputs DIR]
+       aoc2020 batch [--threads N] [--inputs DIR]
+       aoc2020 compare [--threads N] [--inputs DIR]
+       aoc2020 generate --day N [--seed N] [--size N]
+       aoc2020 frames --day N [--part N] [--input PATH|-] [--generations N]
+                      [--format pbm|pgm|ascii] [--out DIR|-]
+
+Without --day every day is run. Without --input the puzzle input is read from
+input/2020/dayN.txt; `-` reads it from stdin. Input files are parsed a line (or
+a blank-line separated block) at a time where the day allows it, so they never
+have to fit in memory whole. CRLF line endings, a byte order mark and trailing
+blank lines are all fine. --example runs the day's Nth example from the
+puzzle description and checks it against the documented answers.
+
+--animate plays the simulation of day 11, 17, 23 (part 1) or 24 back before
+answering, redrawing it in place every --delay milliseconds (100 by default).
+When stdout is not a terminal the frames are printed one after another instead.
+
+--trace prints what the solvers are doing to stderr as they go, one event per
+line. It needs a build with `--features trace`.
+
+--budget gives up on a part once its long-running loop (days 15, 19 and 23,
+and day 22 part 1) has taken that many steps; --progress reports how far along
+it is on stderr every million or so.
+
+--explain follows each answer with the steps that led to it, for the parts
+that can show their working: days 7, 8, 13 and 21, and day 16 part 2.
+
+verify re-solves every answer in the answers file (answers.txt by default)
+against the inputs under DIR (input by default); record writes that file.
+
+profile times the generator and each part separately, --repeat times (1 by
+default), and prints min/median/max for every day with an input.
+
+batch solves every part of every day with an input on --threads worker threads
+(one per CPU by default) and prints all answers, errors and panics together.
+
+compare does the same for several people's inputs at once, laid out as
+DIR/<user>/dayN.txt (DIR is inputs by default), and prints a matrix of answers
+and timings with a column per user.
+
+generate prints a random input for a day. The same seed (0 by default) always
+gives the same input; --size (100 by default) scales it, as far as the day's
+input format allows. Those caps are 100 adapters rated up to 255 jolts for day
+10, 9 buses for day 13 and 20 ticket fields for day 16, and day 23 is always
+nine cups; smaller ones on other days are listed in the docs for
+`generate::generate`.
+
+frames dumps every generation of day 11, 17 or 24 (part 1 by default) as
+numbered images or text frames in DIR (frames by default), or prints them with
+`--out -`. Day 17 gets one frame per z/w slice.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Plain,
+    Json,
+}
+
+#[derive(Debug)]
+struct Options {
+    day: Option<u8>,
+    part: Option<u8>,
+    input: Option<String>,
+    example: Option<usize>,
+    time: bool,
+    format: Format,
+    animate: bool,
+    delay: Duration,
+    trace: bool,
+    explain: bool,
+    budget: Option<u64>,
+    progress: bool,
+}
+
+fn parse_number<T: FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} needs a value", flag))?;
+    value
+        .parse()
+        .map_err(|_| format!("{} expects a number, got `{}`", flag, value))
+}
+
+fn parse_options(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        day: None,
+        part: None,
+        input: None,
+        example: None,
+        time: false,
+        format: Format::Plain,
+        animate: false,
+        delay: Duration::from_millis(100),
+        trace: false,
+        explain: false,
+        budget: None,
+        progress: false,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => options.day = Some(parse_number("--day", args.next())?),
+            "--part" => options.part = Some(parse_number("--part", args.next())?),
+            "--input" => options.input = Some(args.next().ok_or("--input needs a path or `-`")?),
+            "--example" => options.example = Some(parse_number("--example", args.next())?),
+            "--time" => options.time = true,
+            "--animate" => options.animate = true,
+            "--trace" if cfg!(feature = "trace") => options.trace = true,
+            "--trace" => return Err("--trace needs a build with `--features trace`".to_owned()),
+            "--explain" => options.explain = true,
+            "--budget" => options.budget = Some(parse_number("--budget", args.next())?),
+            "--progress" => options.progress = true,
+            "--delay" => {
+                options.delay = Duration::from_millis(parse_number("--delay", args.next())?)
+            }
+            "--format" => {
+                options.format = match args.next().as_deref() {
+                    Some("plain") => Format::Plain,
+                    Some("json") => Format::Json,
+                    _ => return Err("--format expects `plain` or `json`".to_owned()),
+                }
+            }
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    if options.day.is_none() {
+        if options.part.is_some() {
+            return Err("--part needs --day".to_owned());
+        }
+        if options.input.is_some() {
+            return Err("--input needs --day".to_owned());
+        }
+        if options.example.is_some() {
+            return Err("--example needs --day".to_owned());
+        }
+        if options.animate {
+            return Err("--animate needs --day".to_owned());
+        }
+    }
+    if options.input.is_some() && options.example.is_some() {
+        return Err("--input and --example can't be used together".to_owned());
+    }
+    if options.explain && options.format == Format::Json {
+        return Err("--explain only works with plain output".to_owned());
+    }
+
+    Ok(options)
+}
+
+fn read_input(day: u8, path: Option<&str>) -> Result<String, String> {
+    match path {
+        Some("-") => {
+            let mut input = String::new();
+            io::stdin()
+                .read_to_string(&mut input)
+                .map_err(|e| format!("could not read stdin: {}", e))?;
+            Ok(input)
+        }
+        Some(path) => {
+            fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path, e))
+        }
+        None => {
+            let path = input_path(Path::new("input"), day);
+            fs::read_to_string(&path)
+                .map_err(|e| format!("could not read {}: {}", path.display(), e))
+        }
+    }
+}
+
+/// Where a day's input comes from. Files are read afresh, a line at a time where the day
+/// allows it, for every part; stdin can only be read once, so it is kept as text.
+enum Input {
+    Text(String),
+    File(PathBuf),
+}
+
+impl Input {
+    fn locate(day: u8, path: Option<&str>) -> Result<Input, String> {
+        match path {
+            Some("-") => read_input(day, path).map(Input::Text),
+            Some(path) => Ok(Input::File(path.into())),
+            None => Ok(Input::File(input_path(Path::new("input"), day))),
+        }
+    }
+
+    fn text(&self) -> Result<String, String> {
+        match self {
+            Input::Text(text) => Ok(text.clone()),
+            Input::File(path) => fs::read_to_string(path)
+                .map_err(|e| format!("could not read {}: {}", path.display(), e)),
+        }
+    }
+
+    fn solve(&self, day: u8, part: u8) -> Result<Answer, String> {
+        let path = match self {
+            Input::Text(text) => return solve(day, part, text).map_err(|e| e.to_string()),
+            Input::File(path) => path,
+        };
+
+        let file = fs::File::open(path)
+            .map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+        solve_reader(day, part, &mut BufReader::new(file)).map_err(|e| match e {
+            Error::Io(e) => format!("could not read {}: {}", path.display(), e),
+            e => e.to_string(),
+        })
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Integer(v) => v.to_string(),
+        Answer::BigInteger(v) => v.to_string(),
+        Answer::Unbounded(v) => v.to_string(),
+        Answer::String(s) => json_string(s),
+        Answer::Structured(fields) => {
+            let fields: Vec<_> = fields
+                .iter()
+                .map(|(k, v)| format!("{}:{}", json_string(k), json_answer(v)))
+                .collect();
+            format!("{{{}}}", fields.join(","))
+        }
+    }
+}
+
+fn report(
+    options: &Options,
+    day: u8,
+    part: u8,
+    result: &Result<Answer, String>,
+    elapsed: Duration,
+) {
+    match options.format {
+        Format::Plain => {
+            let time = if options.time {
+                format!(" ({:.3}ms)", elapsed.as_secs_f64() * 1000.0)
+            } else {
+                String::new()
+            };
+            match result {
+                Ok(answer) => println!("day {} part {}: {}{}", day, part, answer, time),
+                Err(err) => eprintln!("day {} part {}: error: {}", day, part, err),
+            }
+        }
+        Format::Json => {
+            let outcome = match result {
+                Ok(answer) => format!("\"answer\":{}", json_answer(answer)),
+                Err(err) => format!("\"error\":{}", json_string(err)),
+            };
+            let time = if options.time {
+                format!(",\"time_us\":{}", elapsed.as_micros())
+            } else {
+                String::new()
+            };
+            println!("{{\"day\":{},\"part\":{},{}{}}}", day, part, outcome, time);
+        }
+    }
+}
+
+/// Solves one part under `context`, reporting a panic as an error the way batch does, so one
+/// day going wrong doesn't stop the rest from running.
+fn solve_guarded(context: &Context, input: &Input, day: u8, part: u8) -> Result<Answer, String> {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        context::run(context, || input.solve(day, part))
+    }))
+    .map_err(|payload| format!("panicked: {}", batch::panic_message(payload)))?
+    .map_err(|e| e.to_string())?
+}
+
+/// Runs the selected days and parts, returning whether all of them succeeded.
+fn run(options: &Options) -> Result<bool, String> {
+    let selected: Vec<u8> = match options.day {
+        Some(day) if parts(day).is_empty() => return Err(format!("no solution for day {}", day)),
+        Some(day) => vec![day],
+        None => days().collect(),
+    };
+    if options.trace {
+        trace::install(trace::Stderr);
+    }
+    let mut context = Context::new();
+    if let Some(budget) = options.budget {
+        context = context.budget(budget);
+    }
+    if options.progress {
+        context = context.on_progress(1 << 20, |progress| match progress.total {
+            Some(total) => eprintln!(
+                "day {}: {} of {} steps",
+                progress.day, progress.steps, total
+            ),
+            None => eprintln!("day {}: {} steps", progress.day, progress.steps),
+        });
+    }
+
+    let mut ok = true;
+    for day in selected {
+        let example = match options.example {
+            Some(n) => Some(
+                examples::example(day, n)
+                    .ok_or_else(|| format!("day {} has no example {}", day, n))?,
+            ),
+            None => None,
+        };
+        let input = match example {
+            Some(example) => Ok(Input::Text(example.input.to_owned())),
+            None => Input::locate(day, options.input.as_deref()),
+        };
+        let day_parts = match options.part {
+            Some(part) if !parts(day).contains(&part) => {
+                return Err(format!("no solution for day {} part {}", day, part))
+            }
+            Some(part) => vec![part],
+            // an example only exercises the parts it documents an answer for
+            None => match example {
+                Some(example) => parts(day)
+                    .iter()
+                    .copied()
+                    .filter(|&part| example.expected(part).is_some())
+                    .collect(),
+                None => parts(day).to_vec(),
+            },
+        };
+
+        for part in day_parts {
+            if let (true, Ok(input)) = (options.animate, &input) {
+                let playback = animate::playback(day, part, &input.text()?, None)
+                    .map_err(|e| e.to_string())?;
+                let stdout = io::stdout();
+                let ansi = stdout.is_terminal();
+                animate::play(&mut stdout.lock(), &playback, options.delay, ansi)
+                    .map_err(|e| format!("could not write to stdout: {}", e))?;
+            }
+
+            let start = Instant::now();
+            let result = match &input {
+                Ok(input) => solve_guarded(&context, input, day, part),
+                Err(err) => Err(err.clone()),
+            };
+            let elapsed = start.elapsed();
+            let expected = example.and_then(|example| example.expected(part));
+            let result = match (result, expected) {
+                (Ok(answer), Some(expected)) if answer.to_string().as_str() != expected => {
+                    Err(format!("expected {}, got {}", expected, answer))
+                }
+                (result, _) => result,
+            };
+
+            ok &= result.is_ok();
+            report(options, day, part, &result, elapsed);
+
+            if let (true, Ok(input), Ok(_)) = (options.explain, &input, &result) {
+                match explain::explain(day, part, &input.text()?) {
+                    Ok(explanation) => {
+                        for (i, step) in explanation.steps.iter().enumerate() {
+                            println!("{}. {}", i + 1, step);
+                        }
+                    }
+                    Err(Error::NoExplanation { .. }) => {}
+                    Err(err) => return Err(err.to_string()),
+                }
+            }
+        }
+    }
+
+    Ok(ok)
+}
+
+struct Registry {
+    answers: PathBuf,
+    inputs: PathBuf,
+}
+
+fn parse_registry(mut args: impl Iterator<Item = String>) -> Result<Registry, String> {
+    let mut registry = Registry {
+        answers: PathBuf::from("answers.txt"),
+        inputs: PathBuf::from("input"),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--answers" => registry.answers = args.next().ok_or("--answers needs a path")?.into(),
+            "--inputs" => registry.inputs = args.next().ok_or("--inputs needs a path")?.into(),
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    Ok(registry)
+}
+
+fn verify(registry: &Registry) -> Result<bool, String> {
+    let text = fs::read_to_string(&registry.answers)
+        .map_err(|e| format!("could not read {}: {}", registry.answers.display(), e))?;
+    let answers = verify::parse_answers(&text)
+        .map_err(|e| format!("{}: {}", registry.answers.display(), e))?;
+
+    let checks = verify::verify(&answers, &registry.inputs);
+    for check in &checks {
+        if check.passed() {
+            println!("{}", check);
+        } else {
+            eprintln!("{}", check);
+        }
+    }
+
+    let failed = checks.iter().filter(|check| !check.passed()).count();
+    println!("{} checked, {} failed", checks.len(), failed);
+    Ok(failed == 0)
+}
+
+fn record(registry: &Registry) -> Result<bool, String> {
+    let answers = verify::record(&registry.inputs).map_err(|e| e.to_string())?;
+    fs::write(&registry.answers, verify::format_answers(&answers))
+        .map_err(|e| format!("could not write {}: {}", registry.answers.display(), e))?;
+
+    println!(
+        "recorded {} answers in {}",
+        answers.len(),
+        registry.answers.display()
+    );
+    Ok(true)
+}
+
+fn profile(mut args: impl Iterator<Item = String>) -> Result<bool, String> {
+    let mut day = None;
+    let mut repeat: usize = 1;
+    let mut inputs = PathBuf::from("input");
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number("--day", args.next())?),
+            "--repeat" => repeat = parse_number::<usize>("--repeat", args.next())?.max(1),
+            "--inputs" => inputs = args.next().ok_or("--inputs needs a path")?.into(),
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    let selected: Vec<u8> = match day {
+        Some(day) if parts(day).is_empty() => return Err(format!("no solution for day {}", day)),
+        Some(day) => vec![day],
+        None => days().collect(),
+    };
+    let all = day.is_none();
+
+    let mut ok = true;
+    let mut profiles = vec![];
+    for day in selected {
+        let path = input_path(&inputs, day);
+        let input = match fs::read_to_string(&path) {
+            Ok(input) => input,
+            // only complain about a missing input when the day was asked for
+            Err(_) if all => continue,
+            Err(e) => return Err(format!("could not read {}: {}", path.display(), e)),
+        };
+
+        match profile::profile(day, &input, repeat) {
+            Ok(profile) => profiles.push(profile),
+            Err(err) => {
+                eprintln!("day {}: {}", day, err);
+                ok = false;
+            }
+        }
+    }
+
+    print!("{}", profile::table(&profiles));
+    Ok(ok)
+}
+
+fn run_batch(mut args: impl Iterator<Item = String>) -> Result<bool, String> {
+    let mut threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut inputs = PathBuf::from("input");
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--threads" => threads = parse_number::<usize>("--threads", args.next())?.max(1),
+            "--inputs" => inputs = args.next().ok_or("--inputs needs a path")?.into(),
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    let mut jobs = vec![];
+    for day in days() {
+        let path = input_path(&inputs, day);
+        let input: Arc<str> = match fs::read_to_string(&path) {
+            Ok(input) => input.into(),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(format!("could not read {}: {}", path.display(), e)),
+        };
+
+        for &part in parts(day) {
+            jobs.push(Job {
+                day,
+                part,
+                input: input.clone(),
+            });
+        }
+    }
+
+    if jobs.is_empty() {
+        return Err(format!("no inputs found in {}", inputs.display()));
+    }
+
+    let batch = batch::run(&jobs, threads);
+    print!("{}", batch::table(&batch));
+    Ok(batch.reports.iter().all(|report| report.passed()))
+}
+
+fn run_compare(mut args: impl Iterator<Item = String>) -> Result<bool, String> {
+    let mut threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut inputs = PathBuf::from("inputs");
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--threads" => threads = parse_number::<usize>("--threads", args.next())?.max(1),
+            "--inputs" => inputs = args.next().ok_or("--inputs needs a path")?.into(),
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    let users = compare::load(&inputs)
+        .map_err(|e| format!("could not read {}: {}", inputs.display(), e))?;
+    if users.iter().all(|user| user.inputs.is_empty()) {
+        return Err(format!("no inputs found in {}", inputs.display()));
+    }
+
+    let matrix = compare::compare(&users, threads);
+    print!("{}", compare::table(&matrix));
+    Ok(matrix.passed())
+}
+
+fn generate(mut args: impl Iterator<Item = String>) -> Result<bool, String> {
+    let mut day = None;
+    let mut seed = 0;
+    let mut size = 100;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number("--day", args.next())?),
+            "--seed" => seed = parse_number("--seed", args.next())?,
+            "--size" => size = parse_number("--size", args.next())?,
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    let day = day.ok_or("generate needs --day")?;
+    println!(
+        "{}",
+        generate::generate(day, seed, size).map_err(|e| e.to_string())?
+    );
+    Ok(true)
+}
+
+fn dump_frames(mut args: impl Iterator<Item = String>) -> Result<bool, String> {
+    let mut day = None;
+    let mut part = 1;
+    let mut input = None;
+    let mut generations = None;
+    let mut format = FrameFormat::Ascii;
+    let mut out = "frames".to_owned();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number("--day", args.next())?),
+            "--part" => part = parse_number("--part", args.next())?,
+            "--input" => input = Some(args.next().ok_or("--input needs a path or `-`")?),
+            "--generations" => generations = Some(parse_number("--generations", args.next())?),
+            "--format" => {
+                format = args
+                    .next()
+                    .and_then(|format| format.parse().ok())
+                    .ok_or("--format expects `pbm`, `pgm` or `ascii`")?
+            }
+            "--out" => out = args.next().ok_or("--out needs a directory or `-`")?,
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    let day = day.ok_or("frames needs --day")?;
+    let input = read_input(day, input.as_deref())?;
+    let frames = frames::frames(day, part, &input, generations).map_err(|e| e.to_string())?;
+
+    if out == "-" {
+        for frame in &frames {
+            println!("{}\n{}", frame.name, format.render(frame));
+        }
+        return Ok(true);
+    }
+
+    let dir = Path::new(&out);
+    fs::create_dir_all(dir).map_err(|e| format!("could not create {}: {}", dir.display(), e))?;
+    for frame in &frames {
+        let path = dir.join(format!(
+            "day{}-part{}-{}.{}",
+            day,
+            part,
+            frame.name,
+            format.extension()
+        ));
+        fs::write(&path, format.render(frame))
+            .map_err(|e| format!("could not write {}: {}", path.display(), e))?;
+    }
+
+    println!("wrote {} frames to {}", frames.len(), dir.display());
+    Ok(true)
+}
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let result = match args.next().as_deref() {
+        Some("run") => parse_options(args).and_then(|options| run(&options)),
+        Some("verify") => parse_registry(args).and_then(|registry| verify(&registry)),
+        Some("record") => parse_registry(args).and_then(|registry| record(&registry)),
+        Some("profile") => profile(args),
+        Some("batch") => run_batch(args),
+        Some("compare") => run_compare(args),
+        Some("generate") => generate(args),
+        Some("frames") => dump_frames(args),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            return;
+        }
+        Some(command) => Err(format!("unknown command `{}`\n\n{}", command, USAGE)),
+        None => Err(USAGE.to_owned()),
+    };
+
+    match result {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use advent_of_code2020::bigint::BigUint;
+
+    fn args(s: &str) -> impl Iterator<Item = String> + '_ {
+        s.split_whitespace().map(str::to_owned)
+    }
+
+    #[test]
+    fn test_options() {
+        let options = parse_options(args("--day 14 --part 2 --input - --format json")).unwrap();
+        assert_eq!(options.day, Some(14));
+        assert_eq!(options.part, Some(2));
+        assert_eq!(options.input.as_deref(), Some("-"));
+        assert_eq!(options.format, Format::Json);
+
+        assert!(parse_options(args("--part 2")).is_err());
+        assert!(parse_options(args("--day x")).is_err());
+        assert!(parse_options(args("--format xml")).is_err());
+        assert!(parse_options(args("--example 1")).is_err());
+        assert!(parse_options(args("--day 1 --example 1 --input -")).is_err());
+
+        let options = parse_options(args("--day 11 --animate --delay 20")).unwrap();
+        assert!(options.animate);
+        assert_eq!(options.delay, Duration::from_millis(20));
+        assert!(parse_options(args("--animate")).is_err());
+        assert!(parse_options(args("--explain")).unwrap().explain);
+        assert!(parse_options(args("--explain --format json")).is_err());
+        let options = parse_options(args("--budget 1000 --progress")).unwrap();
+        assert_eq!(options.budget, Some(1000));
+        assert!(options.progress);
+        assert_eq!(
+            parse_options(args("--trace")).map(|options| options.trace),
+            if cfg!(feature = "trace") {
+                Ok(true)
+            } else {
+                Err("--trace needs a build with `--features trace`".to_owned())
+            }
+        );
+    }
+
+    #[test]
+    fn test_solve_guarded() {
+        let context = Context::new();
+        let input = Input::Text("1\n2".to_owned());
+        assert!(solve_guarded(&context, &input, 1, 1)
+            .unwrap_err()
+            .starts_with("panicked: "));
+
+        let input = Input::Text("1721\n979\n366\n299\n675\n1456".to_owned());
+        assert_eq!(solve_guarded(&context, &input, 1, 1), Ok(514579.into()));
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(
+            json_answer(&Answer::structured(vec![("index", 9), ("acc", 8)])),
+            r#"{"index":9,"acc":8}"#
+        );
+        assert_eq!(json_answer(&"a\"b".into()), r#""a\"b""#);
+
+        let max = BigUint::from(u64::MAX);
+        assert_eq!(
+            json_answer(&(&(&max * &max) * &max).into()),
+            "6277101735386680762814942322444851025767571854389858533375"
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! Arithmetic for the solvers whose numbers grow with the input. With the `checked` feature
+//! an overflow comes back from [`crate::solve`] as [`Error::Overflow`]; without it these are
+//! the plain operators, wrapping in release builds and panicking in debug ones.
+
+use std::{
+    any::Any,
+    ops::{Add, Mul, Sub},
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::Error;
+
+/// Unwinds out of a solver that overflowed, to be turned back into an error by [`catch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Overflow {
+    day: u8,
+    operation: &'static str,
+}
+
+pub trait Integer: Sized + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> {
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! integer {
+    ($($t:ty),*) => {
+        $(impl Integer for $t {
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+        })*
+    };
+}
+
+integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+fn overflow(day: u8, operation: &'static str) -> ! {
+    // unlike panic!, this doesn't print anything on the way out
+    panic::resume_unwind(Box::new(Overflow { day, operation }))
+}
+
+pub fn add<T: Integer>(day: u8, operation: &'static str, a: T, b: T) -> T {
+    if cfg!(feature = "checked") {
+        a.checked_add(b).unwrap_or_else(|| overflow(day, operation))
+    } else {
+        a + b
+    }
+}
+
+pub fn sub<T: Integer>(day: u8, operation: &'static str, a: T, b: T) -> T {
+    if cfg!(feature = "checked") {
+        a.checked_sub(b).unwrap_or_else(|| overflow(day, operation))
+    } else {
+        a - b
+    }
+}
+
+pub fn mul<T: Integer>(day: u8, operation: &'static str, a: T, b: T) -> T {
+    if cfg!(feature = "checked") {
+        a.checked_mul(b).unwrap_or_else(|| overflow(day, operation))
+    } else {
+        a * b
+    }
+}
+
+/// Sums `values`, like `Iterator::sum`.
+pub fn sum<T: Integer + Default>(
+    day: u8,
+    operation: &'static str,
+    values: impl IntoIterator<Item = T>,
+) -> T {
+    values.into_iter().fold(T::default(), |total, value| {
+        add(day, operation, total, value)
+    })
+}
+
+/// Multiplies `values` together, like `Iterator::product`.
+pub fn product<T: Integer + From<u8>>(
+    day: u8,
+    operation: &'static str,
+    values: impl IntoIterator<Item = T>,
+) -> T {
+    values
+        .into_iter()
+        .fold(T::from(1), |total, value| mul(day, operation, total, value))
+}
+
+/// Moves `index` by a signed `offset`, wrapping around rather than going below zero when
+/// unchecked.
+pub fn offset(day: u8, operation: &'static str, index: usize, offset: isize) -> usize {
+    if cfg!(feature = "checked") {
+        index
+            .checked_add_signed(offset)
+            .unwrap_or_else(|| overflow(day, operation))
+    } else {
+        index.wrapping_add_signed(offset)
+    }
+}
+
+/// Unwraps an `Option` that is `None` when a calculation overflowed, reporting that as an
+/// overflow in `day`. Without the `checked` feature it is a plain panic, as there's no
+/// wrapped value to carry on with.
+pub fn expect<T>(day: u8, operation: &'static str, value: Option<T>) -> T {
+    match value {
+        Some(value) => value,
+        None if cfg!(feature = "checked") => overflow(day, operation),
+        None => panic!("arithmetic overflow in day {}: {}", day, operation),
+    }
+}
+
+/// Runs a solver, turning an overflow inside it into an error. Any other panic carries on.
+pub fn catch<T>(f: impl FnOnce() -> T) -> Result<T, Error> {
+    if !cfg!(feature = "checked") {
+        return Ok(f());
+    }
+
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload: Box<dyn Any + Send>| {
+        match payload.downcast::<Overflow>() {
+            Ok(overflow) => Error::Overflow {
+                day: overflow.day,
+                operation: overflow.operation,
+            },
+            Err(payload) => panic::resume_unwind(payload),
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(add(1, "test", 200u8, 55), 255);
+        assert_eq!(sub(1, "test", 5i16, 7), -2);
+        assert_eq!(mul(1, "test", 1u64 << 32, 1 << 31), 1 << 63);
+        assert_eq!(sum(1, "test", vec![1u8, 2, 3]), 6);
+        assert_eq!(product(1, "test", vec![2u8, 3, 4]), 24);
+        assert_eq!(offset(1, "test", 5, -3), 2);
+        assert_eq!(expect(1, "test", Some(4)), 4);
+        assert_eq!(catch(|| add(1, "test", 1u8, 2)), Ok(3));
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_overflow() {
+        assert_eq!(
+            catch(|| add(8, "accumulator", i16::MAX, 1)),
+            Err(Error::Overflow {
+                day: 8,
+                operation: "accumulator"
+            })
+        );
+        assert_eq!(
+            catch(|| offset(8, "jump", 1, -2)),
+            Err(Error::Overflow {
+                day: 8,
+                operation: "jump"
+            })
+        );
+        assert_eq!(
+            catch(|| expect::<u8>(13, "timestamp", None)),
+            Err(Error::Overflow {
+                day: 13,
+                operation: "timestamp"
+            })
+        );
+        assert!(panic::catch_unwind(|| catch(|| panic!("not an overflow"))).is_err());
+
+        assert_eq!(
+            crate::solve(8, 1, "acc +30000\nacc +30000"),
+            Err(Error::Overflow {
+                day: 8,
+                operation: "accumulator"
+            })
+        );
+        assert_eq!(
+            crate::solve(12, 1, "F2000000000\nF2000000000"),
+            Err(Error::Overflow {
+                day: 12,
+                operation: "position"
+            })
+        );
+        assert_eq!(
+            crate::solve(1, 2, "4294967295\n1\n2"),
+            Err(Error::Overflow {
+                day: 1,
+                operation: "sum"
+            })
+        );
+
+        // six departure fields, each pinned to a value near the top of a u16
+        let fields: Vec<_> = "abcdef"
+            .chars()
+            .enumerate()
+            .map(|(i, c)| format!("departure {}: {}-{} or 0-0", c, 60000 + i, 60000 + i))
+            .collect();
+        let ticket = (60000..60006)
+            .map(|n: u32| n.to_string())
+            .collect::<Vec<_>>();
+        let tickets = format!(
+            "{}\n\nyour ticket:\n{}\n\nnearby tickets:\n{}",
+            fields.join("\n"),
+            ticket.join(","),
+            ticket.join(",")
+        );
+        assert_eq!(
+            crate::solve(16, 2, &tickets),
+            Err(Error::Overflow {
+                day: 16,
+                operation: "departure product"
+            })
+        );
+
+        // shiny gold holds 9 bags, each holding 9 more, 30 deep
+        let bags = (0..30)
+            .map(|i| format!("bag{} shade bags contain 9 bag{} shade bags.", i, i + 1))
+            .chain(vec![
+                "shiny gold bags contain 9 bag0 shade bags.".to_owned(),
+                "bag30 shade bags contain no other bags.".to_owned(),
+            ])
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(
+            crate::solve(7, 2, &bags),
+            Err(Error::Overflow {
+                day: 7,
+                operation: "bag count"
+            })
+        );
+
+        // exact answers have nothing to overflow
+        if !cfg!(feature = "exact") {
+            assert_eq!(
+                crate::solve(18, 2, "99999999999 * 99999999999"),
+                Err(Error::Overflow {
+                    day: 18,
+                    operation: "multiplication"
+                })
+            );
+        }
+    }
+}
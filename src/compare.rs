@@ -0,0 +1,242 @@
+use std::{
+    fmt::Write,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::batch::{self, Job, Outcome, Report};
+use crate::{days, parts};
+
+/// One person's puzzle inputs, by day.
+#[derive(Debug, Clone)]
+pub struct User {
+    pub name: String,
+    pub inputs: Vec<(u8, Arc<str>)>,
+}
+
+/// Where `user`'s input for `day` lives under `dir`.
+pub fn input_path(dir: &Path, user: &str, day: u8) -> PathBuf {
+    dir.join(user).join(format!("day{}.txt", day))
+}
+
+/// Reads every subdirectory of `dir` as one user's inputs, sorted by name. Days a user has
+/// no input for are left out.
+pub fn load(dir: &Path) -> io::Result<Vec<User>> {
+    let mut users = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if !entry.file_type()?.is_dir() {
+            continue;
+        }
+
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let mut inputs = vec![];
+        for day in days() {
+            match fs::read_to_string(input_path(dir, &name, day)) {
+                Ok(input) => inputs.push((day, input.into())),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        users.push(User { name, inputs });
+    }
+
+    users.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(users)
+}
+
+/// One day and part, with a report for each user in turn; `None` where they have no input.
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub day: u8,
+    pub part: u8,
+    pub cells: Vec<Option<Report>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Matrix {
+    pub users: Vec<String>,
+    pub rows: Vec<Row>,
+    pub threads: usize,
+    pub wall: Duration,
+}
+
+impl Matrix {
+    pub fn passed(&self) -> bool {
+        self.rows
+            .iter()
+            .flat_map(|row| row.cells.iter().flatten())
+            .all(Report::passed)
+    }
+}
+
+/// Solves every part of every day for every user as one [`batch`], so a solver that
+/// panics on one person's input only loses that cell.
+pub fn compare(users: &[User], threads: usize) -> Matrix {
+    let mut jobs = vec![];
+    // (row, column) of each job
+    let mut cells = vec![];
+    let mut rows = vec![];
+
+    for day in days() {
+        for &part in parts(day) {
+            for (column, user) in users.iter().enumerate() {
+                if let Some((_, input)) = user.inputs.iter().find(|(d, _)| *d == day) {
+                    jobs.push(Job {
+                        day,
+                        part,
+                        input: input.clone(),
+                    });
+                    cells.push((rows.len(), column));
+                }
+            }
+            rows.push(Row {
+                day,
+                part,
+                cells: vec![None; users.len()],
+            });
+        }
+    }
+
+    let batch = batch::run(&jobs, threads);
+    for (report, (row, column)) in batch.reports.into_iter().zip(cells) {
+        rows[row].cells[column] = Some(report);
+    }
+    // nobody has an input for these
+    rows.retain(|row| row.cells.iter().any(Option::is_some));
+
+    Matrix {
+        users: users.iter().map(|user| user.name.clone()).collect(),
+        rows,
+        threads: batch.threads,
+        wall: batch.wall,
+    }
+}
+
+fn cell(report: &Option<Report>) -> String {
+    let report = match report {
+        Some(report) => report,
+        None => return "-".to_owned(),
+    };
+
+    let result = match &report.outcome {
+        Outcome::Solved(answer) => answer.to_string(),
+        Outcome::Failed(err) => format!("error: {}", err),
+        Outcome::Panicked(message) => format!("panicked: {}", message),
+    };
+    format!(
+        "{} ({:.3} ms)",
+        result.replace('\n', " "),
+        report.elapsed.as_secs_f64() * 1000.0
+    )
+}
+
+/// Renders one row per day and part, with a column of answers and timings per user.
+pub fn table(matrix: &Matrix) -> String {
+    let cells: Vec<Vec<String>> = matrix
+        .rows
+        .iter()
+        .map(|row| row.cells.iter().map(cell).collect())
+        .collect();
+    let widths: Vec<usize> = matrix
+        .users
+        .iter()
+        .enumerate()
+        .map(|(column, user)| {
+            cells
+                .iter()
+                .map(|row| row[column].len())
+                .chain(Some(user.len()))
+                .max()
+                .unwrap()
+        })
+        .collect();
+
+    let mut out = format!("{:>3}  {:>4}", "day", "part");
+    for (user, width) in matrix.users.iter().zip(&widths) {
+        write!(out, "  {:<width$}", user, width = width).unwrap();
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+
+    for (row, cells) in matrix.rows.iter().zip(&cells) {
+        let mut line = format!("{:>3}  {:>4}", row.day, row.part);
+        for (cell, width) in cells.iter().zip(&widths) {
+            write!(line, "  {:<width$}", cell, width = width).unwrap();
+        }
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    }
+
+    let failed = matrix
+        .rows
+        .iter()
+        .flat_map(|row| row.cells.iter().flatten())
+        .filter(|report| !report.passed())
+        .count();
+    writeln!(
+        out,
+        "{} users on {} threads, {} failed: {:.3} ms wall",
+        matrix.users.len(),
+        matrix.threads,
+        failed,
+        matrix.wall.as_secs_f64() * 1000.0
+    )
+    .unwrap();
+
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn user(name: &str, inputs: &[(u8, &str)]) -> User {
+        User {
+            name: name.to_owned(),
+            inputs: inputs
+                .iter()
+                .map(|&(day, input)| (day, input.into()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        let users = [
+            user("alice", &[(25, "5764801\n17807724")]),
+            user("bob", &[(9, "1"), (25, "17807724\n5764801")]),
+        ];
+
+        let matrix = compare(&users, 2);
+        assert_eq!(matrix.users, ["alice", "bob"]);
+        assert_eq!(
+            matrix
+                .rows
+                .iter()
+                .map(|row| (row.day, row.part))
+                .collect::<Vec<_>>(),
+            [(9, 1), (9, 2), (25, 1)]
+        );
+
+        let outcome = |row: usize, column: usize| {
+            matrix.rows[row].cells[column]
+                .as_ref()
+                .map(|report| report.outcome.clone())
+        };
+        assert_eq!(outcome(0, 0), None);
+        assert!(matches!(outcome(1, 1), Some(Outcome::Panicked(_))));
+        assert_eq!(outcome(2, 0), Some(Outcome::Solved(14897079.into())));
+        assert_eq!(outcome(2, 1), Some(Outcome::Solved(14897079.into())));
+        assert!(!matrix.passed());
+
+        let table = table(&matrix);
+        assert!(table.starts_with("day  part  alice"));
+        assert!(table.lines().nth(1).unwrap().starts_with("  9     1  -  "));
+        assert!(table.ends_with(&format!(
+            "2 users on 2 threads, 1 failed: {:.3} ms wall\n",
+            matrix.wall.as_secs_f64() * 1000.0
+        )));
+    }
+}
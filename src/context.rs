@@ -0,0 +1,286 @@
+//! Progress reports, cancellation and step budgets for the solvers that can run for a long
+//! time: day 15's 30 million turns, day 23's 10 million moves, day 19's rule 42
+//! repetitions and day 22's games of Combat, which can go on forever. Those loops count their iterations with a [`Ticker`], which does nothing
+//! unless the solver is running under a [`Context`] by way of [`run`].
+
+use std::{
+    any::Any,
+    cell::RefCell,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crate::Error;
+
+/// How far a solver has got: `steps` loop iterations so far, out of `total` if it knows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub day: u8,
+    pub steps: u64,
+    pub total: Option<u64>,
+}
+
+/// A flag that stops the solver at its next step once raised, from any thread.
+#[derive(Debug, Clone, Default)]
+pub struct Cancel(Arc<AtomicBool>);
+
+impl Cancel {
+    pub fn new() -> Self {
+        Cancel::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+type Callback = Arc<dyn Fn(Progress) + Send + Sync>;
+
+#[derive(Clone)]
+pub struct Context {
+    progress: Option<(u64, Callback)>,
+    cancel: Cancel,
+    budget: Option<u64>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Context {
+            progress: None,
+            cancel: Cancel::new(),
+            budget: None,
+        }
+    }
+
+    /// Calls `callback` every `every` steps.
+    pub fn on_progress(
+        mut self,
+        every: u64,
+        callback: impl Fn(Progress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some((every.max(1), Arc::new(callback)));
+        self
+    }
+
+    pub fn cancel_with(mut self, cancel: Cancel) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Gives up with [`Error::BudgetExceeded`] after `steps` steps.
+    pub fn budget(mut self, steps: u64) -> Self {
+        self.budget = Some(steps);
+        self
+    }
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Context::new()
+    }
+}
+
+struct Active {
+    context: Context,
+    steps: u64,
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Option<Active>> = const { RefCell::new(None) };
+}
+
+/// Unwinds out of a solver that has to stop, to be turned back into an error by [`run`].
+struct Stop(Error);
+
+/// How many iterations a [`Ticker`] counts before checking in, so the hot loops barely
+/// notice it. Cancellation and budgets take effect within this many steps.
+const BATCH: u64 = 1024;
+
+/// Counts the iterations of one long-running loop in `day`, out of `total` if the solver
+/// knows how many it will take.
+pub struct Ticker {
+    day: u8,
+    total: Option<u64>,
+    pending: u64,
+}
+
+impl Ticker {
+    pub fn new(day: u8, total: Option<u64>) -> Self {
+        Ticker {
+            day,
+            total,
+            pending: 0,
+        }
+    }
+
+    #[inline]
+    pub fn tick(&mut self) {
+        self.pending += 1;
+        if self.pending == BATCH {
+            self.pending = 0;
+            advance(self.day, BATCH, self.total);
+        }
+    }
+}
+
+impl Drop for Ticker {
+    fn drop(&mut self) {
+        // unwinding out of a stop already, which another one would turn into an abort
+        if self.pending > 0 && !std::thread::panicking() {
+            advance(self.day, self.pending, self.total);
+        }
+    }
+}
+
+fn advance(day: u8, steps: u64, total: Option<u64>) {
+    let checked_in = ACTIVE.with(|active| {
+        let mut active = active.borrow_mut();
+        let active = active.as_mut()?;
+        let before = active.steps;
+        active.steps += steps;
+
+        let context = &active.context;
+        let over_budget = context.budget.filter(|budget| active.steps > *budget);
+        let report = match &context.progress {
+            Some((every, callback)) if before / every != active.steps / every => {
+                let progress = Progress {
+                    day,
+                    steps: active.steps,
+                    total,
+                };
+                Some((callback.clone(), progress))
+            }
+            _ => None,
+        };
+        Some((context.cancel.clone(), over_budget, report))
+    });
+    let (cancel, over_budget, report) = match checked_in {
+        Some(checked_in) => checked_in,
+        None => return,
+    };
+
+    // outside the borrow, so the callback can run solvers of its own, and before checking
+    // for cancellation, so it can cancel this one
+    if let Some((callback, progress)) = report {
+        callback(progress);
+    }
+    let stop = if cancel.is_cancelled() {
+        Error::Cancelled(day)
+    } else if let Some(budget) = over_budget {
+        Error::BudgetExceeded { day, budget }
+    } else {
+        return;
+    };
+    panic::resume_unwind(Box::new(Stop(stop)));
+}
+
+/// Runs `f` under `context`, turning a cancellation or an exhausted budget inside it into an
+/// error. Any other panic carries on.
+pub fn run<T>(context: &Context, f: impl FnOnce() -> T) -> Result<T, Error> {
+    let active = Active {
+        context: context.clone(),
+        steps: 0,
+    };
+    let previous = ACTIVE.with(|cell| cell.replace(Some(active)));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    ACTIVE.with(|cell| cell.replace(previous));
+
+    result.map_err(
+        |payload: Box<dyn Any + Send>| match payload.downcast::<Stop>() {
+            Ok(stop) => stop.0,
+            Err(payload) => panic::resume_unwind(payload),
+        },
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::Mutex;
+
+    use crate::solve_with;
+
+    #[test]
+    fn test_budget() {
+        let context = Context::new().budget(3000);
+        assert_eq!(
+            solve_with(15, 2, "0,3,6", &context),
+            Err(Error::BudgetExceeded {
+                day: 15,
+                budget: 3000
+            })
+        );
+        assert_eq!(solve_with(15, 1, "0,3,6", &context), Ok(436.into()));
+
+        assert_eq!(
+            solve_with(23, 2, "389125467", &context),
+            Err(Error::BudgetExceeded {
+                day: 23,
+                budget: 3000
+            })
+        );
+
+        assert_eq!(
+            solve_with(22, 1, "Player 1:\n43\n19\n\nPlayer 2:\n2\n29\n14", &context),
+            Err(Error::BudgetExceeded {
+                day: 22,
+                budget: 3000
+            })
+        );
+
+        // outside a context the ticks go nowhere
+        let mut ticker = Ticker::new(15, None);
+        (0..BATCH).for_each(|_| ticker.tick());
+        drop(ticker);
+        assert_eq!(run(&context, || ()), Ok(()));
+        assert!(
+            panic::catch_unwind(AssertUnwindSafe(|| run(&context, || panic!("not a stop"))))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cancel() {
+        let cancel = Cancel::new();
+        let context = Context::new().cancel_with(cancel.clone());
+        assert_eq!(solve_with(15, 1, "0,3,6", &context), Ok(436.into()));
+
+        cancel.cancel();
+        assert_eq!(
+            solve_with(15, 1, "0,3,6", &context),
+            Err(Error::Cancelled(15))
+        );
+
+        // cancelling from the progress callback stops the solver where it is
+        let cancel = Cancel::new();
+        let seen = Arc::new(Mutex::new(vec![]));
+        let context = Context::new()
+            .cancel_with(cancel.clone())
+            .on_progress(1000, {
+                let seen = seen.clone();
+                move |progress| {
+                    seen.lock().unwrap().push(progress);
+                    cancel.cancel();
+                }
+            });
+        assert_eq!(
+            solve_with(15, 2, "0,3,6", &context),
+            Err(Error::Cancelled(15))
+        );
+        assert_eq!(
+            *seen.lock().unwrap(),
+            vec![Progress {
+                day: 15,
+                steps: 1024,
+                total: Some(29999997)
+            }]
+        );
+    }
+}
@@ -1,29 +1,101 @@
+use std::{cmp::Ordering, collections::HashSet, io::BufRead};
+
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 
+use crate::answer::Answer;
+use crate::checked;
+use crate::error::{parse_at, Error, ParseError};
+use crate::puzzle::{Implementation, Puzzle};
+use crate::stream;
+
+fn parse_line(line: &str) -> Result<u32, ParseError> {
+    parse_at(line, line, "an expense amount")
+}
+
 #[aoc_generator(day1)]
-pub fn input_generator(input: &str) -> Vec<u32> {
-    input.lines().map(|e| e.parse().unwrap()).collect()
+pub fn input_generator(input: &str) -> Result<Vec<u32>, ParseError> {
+    stream::lines(input, parse_line)
 }
 
 #[aoc(day1, part1)]
-pub fn solve_p1(input: &[u32]) -> u32 {
-    let opts: Vec<_> = input
-        .iter()
-        .combinations(2)
-        .filter(|v| v.iter().copied().sum::<u32>() == 2020)
-        .map(|v| v.iter().copied().product())
-        .collect();
-    opts[0]
+pub fn solve_p1(input: &[u32]) -> Answer {
+    let mut seen = HashSet::new();
+    for &a in input {
+        if a <= 2020 && seen.contains(&(2020 - a)) {
+            return checked::mul(1, "product", a, 2020 - a).into();
+        }
+        seen.insert(a);
+    }
+    panic!("no two entries sum to 2020")
 }
 
 #[aoc(day1, part2)]
-pub fn solve_p2(input: &[u32]) -> u32 {
+pub fn solve_p2(input: &[u32]) -> Answer {
+    let mut sorted = input.to_vec();
+    sorted.sort_unstable();
+
+    for (i, &a) in sorted.iter().enumerate() {
+        let (mut lo, mut hi) = (i + 1, sorted.len() - 1);
+        while lo < hi {
+            let (b, c) = (sorted[lo], sorted[hi]);
+            match checked::add(1, "sum", checked::add(1, "sum", a, b), c).cmp(&2020) {
+                Ordering::Less => lo += 1,
+                Ordering::Greater => hi -= 1,
+                Ordering::Equal => {
+                    return checked::mul(1, "product", checked::mul(1, "product", a, b), c).into()
+                }
+            }
+        }
+    }
+    panic!("no three entries sum to 2020")
+}
+
+fn combinations(input: &[u32], k: usize) -> Answer {
     let opts: Vec<_> = input
         .iter()
-        .combinations(3)
-        .filter(|v| v.iter().copied().sum::<u32>() == 2020)
-        .map(|v| v.iter().copied().product())
+        .combinations(k)
+        .filter(|v| checked::sum(1, "sum", v.iter().map(|&&x| x)) == 2020)
+        .map(|v| checked::product(1, "product", v.iter().map(|&&x| x)))
         .collect();
-    opts[0]
+    opts[0].into()
+}
+
+pub struct Day1;
+
+impl Puzzle for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<u32>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn read(reader: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        stream::read_lines(reader, parse_line)
+    }
+
+    fn implementations() -> Vec<Implementation<Self::Input>> {
+        vec![
+            Implementation {
+                part: 1,
+                name: "combinations",
+                solve: |input| combinations(input, 2),
+            },
+            Implementation {
+                part: 2,
+                name: "combinations",
+                solve: |input| combinations(input, 3),
+            },
+        ]
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
 }
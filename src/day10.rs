@@ -1,37 +1,65 @@
+use std::io::BufRead;
+
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::answer::Answer;
+use crate::bigint::{Exact, Natural};
+use crate::checked;
+use crate::error::{parse_at, Error, ParseError};
+use crate::puzzle::Puzzle;
+use crate::stream;
+
+fn parse_line(line: &str) -> Result<u8, ParseError> {
+    parse_at(line, line, "a joltage rating from 0 to 255")
+}
+
 #[aoc_generator(day10)]
-pub fn input_generator(input: &str) -> Vec<u8> {
-    let _ = "16\n10\n15\n5\n1\n11\n7\n19\n6\n12\n4";
-    let _ = "28\n33\n18\n42\n31\n14\n46\n20\n48\n47\n24\n23\n49\n45\n19\n38\n39\n11\n1\n32\n25\n35\n8\n17\n7\n9\n4\n2\n34\n10\n3";
-    input.lines().map(|e| e.parse().unwrap()).collect()
+pub fn input_generator(input: &str) -> Result<Vec<u8>, ParseError> {
+    stream::lines(input, parse_line)
 }
 
 #[aoc(day10, part1)]
-pub fn solve_p1(input: &[u8]) -> usize {
-    let mut arr = input.iter().copied().collect::<Vec<_>>();
+pub fn solve_p1(input: &[u8]) -> Answer {
+    // the outlet is rated 0
+    let mut arr = vec![0];
+    arr.extend_from_slice(input);
     arr.sort_unstable();
 
-    let jumps = arr.windows(2).fold((1, 1), |(ones, threes), window| {
-        if window[0] + 1 == window[1] {
-            (ones + 1, threes)
-        } else if window[0] + 3 == window[1] {
-            (ones, threes + 1)
-        } else {
-            unreachable!()
-        }
-    });
+    // the device is always 3 above the highest adapter
+    let jumps = arr
+        .windows(2)
+        .fold((0, 0, 1), |(ones, twos, threes), window| {
+            match window[1] - window[0] {
+                1 => (ones + 1, twos, threes),
+                2 => (ones, twos + 1, threes),
+                3 => (ones, twos, threes + 1),
+                _ => panic!(
+                    "no adapter chain gets from {} to {} jolts",
+                    window[0], window[1]
+                ),
+            }
+        });
 
-    // println!("{:?}", jumps);
+    trace!(
+        10,
+        "jumps",
+        ones = jumps.0,
+        twos = jumps.1,
+        threes = jumps.2
+    );
 
-    jumps.0 * jumps.1
+    checked::mul(10, "jump product", jumps.0, jumps.2).into()
 }
 
 #[aoc(day10, part2)]
-pub fn solve_p2(input: &[u8]) -> u64 {
-    let mut adapters = input.iter().copied().collect::<Vec<_>>();
+pub fn solve_p2(input: &[u8]) -> Answer {
+    arrangements::<Exact>(input).into()
+}
+
+fn arrangements<N: Natural>(input: &[u8]) -> N {
+    let mut adapters = input.to_vec();
     adapters.sort_unstable();
-    let mut permutations_to_end_from_ind = vec![0; adapters.len()];
+    let mut permutations_to_end_from_ind = vec![N::default(); adapters.len()];
 
     adapters
         .iter()
@@ -39,55 +67,128 @@ pub fn solve_p2(input: &[u8]) -> u64 {
         .rev()
         .for_each(|(ind, joltage)| {
             if ind == adapters.len() - 1 {
-                permutations_to_end_from_ind[ind] = 1;
+                permutations_to_end_from_ind[ind] = N::from(1);
             } else if ind == adapters.len() - 2 {
                 // specific case: 1 from the end
-                let mut permutations_from_here = 0;
+                let mut permutations_from_here = N::default();
 
                 if adapters[ind + 1] - joltage <= 3 {
-                    permutations_from_here += permutations_to_end_from_ind[ind + 1];
+                    permutations_from_here = checked::add(
+                        10,
+                        "arrangements",
+                        permutations_from_here,
+                        permutations_to_end_from_ind[ind + 1].clone(),
+                    );
                 }
 
                 permutations_to_end_from_ind[ind] = permutations_from_here;
             } else if ind == adapters.len() - 3 {
                 // specific case: we're 2 from the end
-                let mut permutations_from_here = 0;
+                let mut permutations_from_here = N::default();
 
                 if adapters[ind + 2] - joltage <= 3 {
-                    permutations_from_here += permutations_to_end_from_ind[ind + 2];
+                    permutations_from_here = checked::add(
+                        10,
+                        "arrangements",
+                        permutations_from_here,
+                        permutations_to_end_from_ind[ind + 2].clone(),
+                    );
                 }
 
                 if adapters[ind + 1] - joltage <= 3 {
-                    permutations_from_here += permutations_to_end_from_ind[ind + 1];
+                    permutations_from_here = checked::add(
+                        10,
+                        "arrangements",
+                        permutations_from_here,
+                        permutations_to_end_from_ind[ind + 1].clone(),
+                    );
                 }
 
                 permutations_to_end_from_ind[ind] = permutations_from_here;
             } else {
                 // try up to 3 adapters after this one
                 // general case - we're 3 or more from the end
-                let mut permutations_from_here = 0;
+                let mut permutations_from_here = N::default();
 
                 if adapters[ind + 3] - joltage <= 3 {
-                    permutations_from_here += permutations_to_end_from_ind[ind + 3];
+                    permutations_from_here = checked::add(
+                        10,
+                        "arrangements",
+                        permutations_from_here,
+                        permutations_to_end_from_ind[ind + 3].clone(),
+                    );
                 }
 
                 if adapters[ind + 2] - joltage <= 3 {
-                    permutations_from_here += permutations_to_end_from_ind[ind + 2];
+                    permutations_from_here = checked::add(
+                        10,
+                        "arrangements",
+                        permutations_from_here,
+                        permutations_to_end_from_ind[ind + 2].clone(),
+                    );
                 }
 
                 if adapters[ind + 1] - joltage <= 3 {
-                    permutations_from_here += permutations_to_end_from_ind[ind + 1];
+                    permutations_from_here = checked::add(
+                        10,
+                        "arrangements",
+                        permutations_from_here,
+                        permutations_to_end_from_ind[ind + 1].clone(),
+                    );
                 }
 
                 permutations_to_end_from_ind[ind] = permutations_from_here;
             }
-            // println!(
-            //     "{}, {} -> {}",
-            //     ind, joltage, permutations_to_end_from_ind[ind]
-            // );
+            trace!(
+                10,
+                "permutations",
+                ind,
+                joltage,
+                permutations = permutations_to_end_from_ind[ind]
+            );
         });
 
-    permutations_to_end_from_ind[0]
-        + permutations_to_end_from_ind[1]
-        + permutations_to_end_from_ind[2]
+    // only adapters within 3 jolts of the outlet can start a chain
+    let starts = adapters
+        .iter()
+        .zip(&permutations_to_end_from_ind)
+        .take_while(|(joltage, _)| **joltage <= 3)
+        .map(|(_, permutations)| permutations.clone());
+    checked::sum(10, "arrangements", starts)
+}
+
+pub struct Day10;
+
+impl Puzzle for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<u8>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn read(reader: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        stream::read_lines(reader, parse_line)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_p1_gaps() {
+        // 0 to 1 is a 1-jolt gap, 1 to 3 a 2-jolt one, then 3 to 6 and 6 to the device
+        assert_eq!(solve_p1(&[1, 3, 6]), 2);
+        assert_eq!(solve_p1(&[3, 2]), 1);
+    }
 }
@@ -1,5 +1,14 @@
+use std::convert::TryFrom;
+
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::answer::Answer;
+use crate::automaton::{Automaton, SquareGrid};
+use crate::error::ParseError;
+use crate::frames::{history, Frame};
+use crate::grid::Grid;
+use crate::puzzle::Puzzle;
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum State {
     Floor,
@@ -7,228 +16,87 @@ pub enum State {
     FilledSeat,
 }
 
-impl State {
-    fn value(&self) -> u8 {
-        match self {
-            State::FilledSeat => 1,
-            _ => 0,
-        }
-    }
-
-    fn apply(&self, nearby: u8) -> Self {
-        match self {
-            State::Floor => State::Floor,
-            State::EmptySeat => {
-                if nearby == 0 {
-                    State::FilledSeat
-                } else {
-                    State::EmptySeat
-                }
-            }
-            State::FilledSeat => {
-                if nearby >= 4 {
-                    State::EmptySeat
-                } else {
-                    State::FilledSeat
-                }
-            }
-        }
-    }
+impl TryFrom<char> for State {
+    type Error = char;
 
-    fn apply2(&self, nearby: u8) -> Self {
-        match self {
-            State::Floor => State::Floor,
-            State::EmptySeat => {
-                if nearby == 0 {
-                    State::FilledSeat
-                } else {
-                    State::EmptySeat
-                }
-            }
-            State::FilledSeat => {
-                if nearby > 4 {
-                    State::EmptySeat
-                } else {
-                    State::FilledSeat
-                }
-            }
-        }
-    }
-}
-
-impl Into<State> for u8 {
-    fn into(self) -> State {
-        match self {
-            b'.' => State::Floor,
-            b'L' => State::EmptySeat,
-            b'#' => State::FilledSeat,
-            _ => unreachable!(),
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '.' => Ok(State::Floor),
+            'L' => Ok(State::EmptySeat),
+            '#' => Ok(State::FilledSeat),
+            _ => Err(c),
         }
     }
 }
 
 #[aoc_generator(day11)]
-pub fn input_generator(input: &str) -> ((usize, usize), Vec<State>) {
-    // let input = "L.LL.LL.LL\nLLLLLLL.LL\nL.L.L..L..\nLLLL.LL.LL\nL.LL.LL.LL\nL.LLLLL.LL\n..L.L.....\nLLLLLLLLLL\nL.LLLLLL.L\nL.LLLLL.LL";
-    let height = bytecount::count(input.as_bytes(), b'\n') + 1;
-    let width = input.as_bytes().iter().take_while(|c| **c != b'\n').count();
-    let layout = input
-        .as_bytes()
-        .iter()
-        .filter(|c| **c != b'\n')
-        .map(|e| (*e).into())
-        .collect();
-    ((height, width), layout)
+pub fn input_generator(input: &str) -> Result<Grid<State>, ParseError> {
+    Grid::parse(input, |c| State::try_from(c).ok(), "`.`, `L` or `#`")
 }
 
-fn index_to_coord(ind: usize, (_, width): &(usize, usize)) -> (usize, usize) {
-    let ht = ind / width;
-    let wd = ind % width;
-    (ht, wd)
+/// Seats fill when nothing they can see is occupied and empty once enough neighbours are:
+/// four adjacent ones in part 1, five in sight in part 2.
+fn seating(layout: &Grid<State>, part: u8) -> Automaton<SquareGrid, impl Fn(bool, usize) -> bool> {
+    let seats = layout.map(|state| *state != State::Floor);
+    let (topology, crowd) = match part {
+        1 => (SquareGrid::adjacent(&seats), 4),
+        _ => (SquareGrid::line_of_sight(&seats), 5),
+    };
+    let filled = layout
+        .positions()
+        .filter(|&pos| layout[pos] == State::FilledSeat);
+
+    Automaton::new(
+        topology,
+        move |filled: bool, nearby: usize| if filled { nearby < crowd } else { nearby == 0 },
+        filled,
+    )
 }
 
-fn coord_to_index(y: usize, x: usize, (_, width): &(usize, usize)) -> usize {
-    y * width + x
+#[aoc(day11, part1)]
+pub fn solve_p1(layout: &Grid<State>) -> Answer {
+    seating(layout, 1).run_until_stable().population().into()
 }
 
-fn nearby_filled_seats((y, x): (usize, usize), spec: &(usize, usize), layout: &[State]) -> u8 {
-    let mut cnt = 0;
-
-    if x > 0 && y > 0 {
-        cnt += layout[coord_to_index(y - 1, x - 1, spec)].value();
-    }
-
-    if y > 0 {
-        cnt += layout[coord_to_index(y - 1, x, spec)].value();
-    }
-
-    if x < spec.1 - 1 && y > 0 {
-        cnt += layout[coord_to_index(y - 1, x + 1, spec)].value();
-    }
-
-    if x > 0 {
-        cnt += layout[coord_to_index(y, x - 1, spec)].value();
-    }
-
-    if x < spec.1 - 1 {
-        cnt += layout[coord_to_index(y, x + 1, spec)].value();
-    }
-
-    if x > 0 && y < spec.0 - 1 {
-        cnt += layout[coord_to_index(y + 1, x - 1, spec)].value();
-    }
-
-    if y < spec.0 - 1 {
-        cnt += layout[coord_to_index(y + 1, x, spec)].value();
-    }
-
-    if x < spec.1 - 1 && y < spec.0 - 1 {
-        cnt += layout[coord_to_index(y + 1, x + 1, spec)].value();
-    }
-
-    cnt
+#[aoc(day11, part2)]
+pub fn solve_p2(layout: &Grid<State>) -> Answer {
+    seating(layout, 2).run_until_stable().population().into()
 }
 
-fn nearest_seat_in_direction(
-    (y, x): (usize, usize),
-    (dy, dx): (isize, isize),
-    spec: &(usize, usize),
-    layout: &[State],
-) -> State {
-    let mut curr_y = y as isize;
-    let mut curr_x = x as isize;
-
-    loop {
-        curr_y += dy;
-        curr_x += dx;
-
-        if curr_x == spec.1 as isize || curr_y == spec.0 as isize || curr_x == -1 || curr_y == -1 {
-            return State::Floor;
-        }
-
-        match layout[coord_to_index(curr_y as usize, curr_x as usize, spec)] {
-            State::Floor => continue,
-            a => return a,
-        }
-    }
+/// One frame per generation, drawn the way the puzzle input is.
+pub fn frames(layout: &Grid<State>, part: u8, generations: usize) -> Vec<Frame> {
+    history(seating(layout, part), generations)
+        .iter()
+        .enumerate()
+        .map(|(generation, filled)| Frame {
+            name: format!("gen{:03}", generation),
+            generation,
+            image: Grid::from_fn(layout.height(), layout.width(), |pos| match layout[pos] {
+                State::Floor => 0,
+                _ if filled.contains(&pos) => 2,
+                _ => 1,
+            }),
+            palette: &['.', 'L', '#'],
+        })
+        .collect()
 }
 
-fn nearby_filled_seats2((y, x): (usize, usize), spec: &(usize, usize), layout: &[State]) -> u8 {
-    let mut cnt = 0;
+pub struct Day11;
 
-    if x > 0 && y > 0 {
-        cnt += nearest_seat_in_direction((y, x), (-1, -1), spec, layout).value();
-    }
+impl Puzzle for Day11 {
+    const DAY: u8 = 11;
 
-    if y > 0 {
-        cnt += nearest_seat_in_direction((y, x), (-1, 0), spec, layout).value();
-    }
+    type Input = Grid<State>;
 
-    if x < spec.1 - 1 && y > 0 {
-        cnt += nearest_seat_in_direction((y, x), (-1, 1), spec, layout).value();
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
     }
 
-    if x > 0 {
-        cnt += nearest_seat_in_direction((y, x), (0, -1), spec, layout).value();
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
     }
 
-    if x < spec.1 - 1 {
-        cnt += nearest_seat_in_direction((y, x), (0, 1), spec, layout).value();
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
     }
-
-    if x > 0 && y < spec.0 - 1 {
-        cnt += nearest_seat_in_direction((y, x), (1, -1), spec, layout).value();
-    }
-
-    if y < spec.0 - 1 {
-        cnt += nearest_seat_in_direction((y, x), (1, 0), spec, layout).value();
-    }
-
-    if x < spec.1 - 1 && y < spec.0 - 1 {
-        cnt += nearest_seat_in_direction((y, x), (1, 1), spec, layout).value();
-    }
-
-    cnt
-}
-
-#[aoc(day11, part1)]
-pub fn solve_p1((spec, layout): &((usize, usize), Vec<State>)) -> usize {
-    let mut last = layout.clone();
-
-    loop {
-        let step = (0..layout.len())
-            .map(|i| nearby_filled_seats(index_to_coord(i, spec), spec, &last))
-            .zip(&last)
-            .map(|(a, b)| b.apply(a))
-            .collect::<Vec<_>>();
-
-        if step.iter().zip(&last).all(|(a, b)| *a == *b) {
-            break;
-        }
-
-        last = step;
-    }
-
-    last.iter().filter(|a| **a == State::FilledSeat).count()
-}
-
-#[aoc(day11, part2)]
-pub fn solve_p2((spec, layout): &((usize, usize), Vec<State>)) -> usize {
-    let mut last = layout.clone();
-
-    loop {
-        let step = (0..layout.len())
-            .map(|i| nearby_filled_seats2(index_to_coord(i, spec), spec, &last))
-            .zip(&last)
-            .map(|(a, b)| b.apply2(a))
-            .collect::<Vec<_>>();
-
-        if step.iter().zip(&last).all(|(a, b)| *a == *b) {
-            break;
-        }
-
-        last = step;
-    }
-
-    last.iter().filter(|a| **a == State::FilledSeat).count()
 }
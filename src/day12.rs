@@ -1,7 +1,13 @@
-use std::{num::ParseIntError, str::FromStr};
+use std::{io::BufRead, num::ParseIntError, str::FromStr};
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::answer::Answer;
+use crate::checked;
+use crate::error::{Error, ParseError};
+use crate::puzzle::Puzzle;
+use crate::stream;
+
 #[derive(Debug, Copy, Clone)]
 pub enum Instruction {
     North(i32),
@@ -13,9 +19,11 @@ pub enum Instruction {
     Forward(i32),
 }
 
+#[derive(Default)]
 pub enum Direction {
     North,
     South,
+    #[default]
     East,
     West,
 }
@@ -60,12 +68,6 @@ impl Direction {
     }
 }
 
-impl Default for Direction {
-    fn default() -> Self {
-        Direction::East
-    }
-}
-
 #[derive(Default)]
 pub struct State {
     x: i32,
@@ -74,18 +76,26 @@ pub struct State {
     facing: Direction,
 }
 
+fn add(a: i32, b: i32) -> i32 {
+    checked::add(12, "position", a, b)
+}
+
+fn sub(a: i32, b: i32) -> i32 {
+    checked::sub(12, "position", a, b)
+}
+
 impl State {
     fn apply(&mut self, instr: Instruction) {
         match instr {
             Instruction::North(num) => {
-                self.y += num;
+                self.y = add(self.y, num);
             }
             Instruction::South(num) => {
-                self.y -= num;
+                self.y = sub(self.y, num);
             }
-            Instruction::East(num) => self.x += num,
+            Instruction::East(num) => self.x = add(self.x, num),
             Instruction::West(num) => {
-                self.x -= num;
+                self.x = sub(self.x, num);
             }
             Instruction::Left(num) => {
                 self.facing = self.facing.rotate(num);
@@ -101,10 +111,10 @@ impl State {
 
     fn apply2(&mut self, instr: Instruction) {
         match instr {
-            Instruction::North(num) => self.waypoint.1 += num,
-            Instruction::South(num) => self.waypoint.1 -= num,
-            Instruction::East(num) => self.waypoint.0 += num,
-            Instruction::West(num) => self.waypoint.0 -= num,
+            Instruction::North(num) => self.waypoint.1 = add(self.waypoint.1, num),
+            Instruction::South(num) => self.waypoint.1 = sub(self.waypoint.1, num),
+            Instruction::East(num) => self.waypoint.0 = add(self.waypoint.0, num),
+            Instruction::West(num) => self.waypoint.0 = sub(self.waypoint.0, num),
 
             Instruction::Left(num) => {
                 self.rotate_waypoint(num);
@@ -113,61 +123,107 @@ impl State {
                 self.rotate_waypoint(360 - num);
             }
             Instruction::Forward(num) => {
-                self.x += self.waypoint.0 * num;
-                self.y += self.waypoint.1 * num;
+                self.x = add(self.x, checked::mul(12, "position", self.waypoint.0, num));
+                self.y = add(self.y, checked::mul(12, "position", self.waypoint.1, num));
             }
         }
     }
 
+    /// The Manhattan distance from the start.
+    fn distance(&self) -> u32 {
+        checked::add(12, "distance", self.x.unsigned_abs(), self.y.unsigned_abs())
+    }
+
     fn rotate_waypoint(&mut self, angle: u32) {
+        let (x, y) = self.waypoint;
         match angle {
-            90 => self.waypoint = (-self.waypoint.1, self.waypoint.0),
-            180 => self.waypoint = (-self.waypoint.0, -self.waypoint.1),
-            270 => self.waypoint = (self.waypoint.1, -self.waypoint.0),
+            90 => self.waypoint = (sub(0, y), x),
+            180 => self.waypoint = (sub(0, x), sub(0, y)),
+            270 => self.waypoint = (y, sub(0, x)),
             _ => unreachable!(),
         }
     }
 }
 
 impl FromStr for Instruction {
-    type Err = ParseIntError;
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (first, rest) = s.split_at(1);
+        let mut chars = s.chars();
+        let first = chars.next();
+        let rest = chars.as_str();
+
+        let distance = || {
+            rest.parse()
+                .map_err(|_: ParseIntError| "a distance like `F10`".to_owned())
+        };
+        let angle = || match rest.parse() {
+            Ok(angle @ 90) | Ok(angle @ 180) | Ok(angle @ 270) => Ok(angle),
+            _ => Err("a turn of 90, 180 or 270 degrees".to_owned()),
+        };
+
         match first {
-            "N" => rest.parse().map(Instruction::North),
-            "S" => rest.parse().map(Instruction::South),
-            "E" => rest.parse().map(Instruction::East),
-            "W" => rest.parse().map(Instruction::West),
-            "L" => rest.parse().map(Instruction::Left),
-            "R" => rest.parse().map(Instruction::Right),
-            "F" => rest.parse().map(Instruction::Forward),
-            _ => unreachable!(),
+            Some('N') => distance().map(Instruction::North),
+            Some('S') => distance().map(Instruction::South),
+            Some('E') => distance().map(Instruction::East),
+            Some('W') => distance().map(Instruction::West),
+            Some('L') => angle().map(Instruction::Left),
+            Some('R') => angle().map(Instruction::Right),
+            Some('F') => distance().map(Instruction::Forward),
+            _ => Err("an action `N`, `S`, `E`, `W`, `L`, `R` or `F`".to_owned()),
         }
     }
 }
 
+fn parse_line(line: &str) -> Result<Instruction, ParseError> {
+    line.parse()
+        .map_err(|expected: String| ParseError::at(line, line, expected))
+}
+
 #[aoc_generator(day12)]
-pub fn input_generator(input: &str) -> Vec<Instruction> {
-    // let input = "F10\nN3\nF7\nR90\nF11";
-    input.lines().map(|instr| instr.parse().unwrap()).collect()
+pub fn input_generator(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    stream::lines(input, parse_line)
 }
 
 #[aoc(day12, part1)]
-pub fn solve_p1(input: &[Instruction]) -> i32 {
-    // println!("{:?}", input);
+pub fn solve_p1(input: &[Instruction]) -> Answer {
+    trace!(12, "input", instructions = input.len());
 
     let mut state: State = Default::default();
     input.iter().for_each(|i| state.apply(*i));
-    state.x.abs() + state.y.abs()
+    state.distance().into()
 }
 
 #[aoc(day12, part2)]
-pub fn solve_p2(input: &[Instruction]) -> i32 {
+pub fn solve_p2(input: &[Instruction]) -> Answer {
     let mut state = State {
         waypoint: (10, 1),
         ..Default::default()
     };
     input.iter().for_each(|i| state.apply2(*i));
-    state.x.abs() + state.y.abs()
+    state.distance().into()
+}
+
+pub struct Day12;
+
+impl Puzzle for Day12 {
+    const DAY: u8 = 12;
+
+    type Input = Vec<Instruction>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn read(reader: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        stream::read_lines(reader, parse_line)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
 }
@@ -1,60 +1,132 @@
-use std::slice::Iter;
-
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::answer::Answer;
+use crate::bigint::Exact;
+use crate::checked;
+use crate::error::{parse_at, ParseError};
+use crate::explain::Step;
+use crate::number_theory::{self, CrtError};
+use crate::puzzle::Puzzle;
+
 #[aoc_generator(day13)]
-pub fn input_generator(input: &str) -> (u64, Vec<Option<u64>>) {
-    let lines = input.lines().collect::<Vec<_>>();
+pub fn input_generator(input: &str) -> Result<(u64, Vec<Option<u64>>), ParseError> {
+    let mut lines = input.lines();
+    let target = lines
+        .next()
+        .ok_or_else(|| ParseError::end_of(input, "an earliest departure time"))?;
+    let buses = lines
+        .next()
+        .ok_or_else(|| ParseError::end_of(input, "a line of bus IDs"))?;
+
+    let target = parse_at(input, target, "an earliest departure time")?;
+    let ids = buses
+        .split(',')
+        .map(|x| match x {
+            "x" => Ok(None),
+            _ => match parse_at(input, x, "a bus ID or `x`")? {
+                0 => Err(ParseError::at(input, x, "a non-zero bus ID")),
+                id => Ok(Some(id)),
+            },
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if ids.iter().all(Option::is_none) {
+        return Err(ParseError::at(input, buses, "at least one bus ID"));
+    }
 
-    (
-        lines[0].parse().unwrap(),
-        lines[1].split(',').map(|x| x.parse().ok()).collect(),
-    )
+    Ok((target, ids))
 }
 
-#[aoc(day13, part1)]
-pub fn solve_p1((target, buses): &(u64, Vec<Option<u64>>)) -> u64 {
-    let (time, bus) = buses
+/// The first bus to leave after `target`, and how long it takes to come.
+fn earliest(target: u64, buses: &[Option<u64>]) -> (u64, u64) {
+    buses
         .iter()
         .filter_map(|i| *i)
         .map(|i| (i - (target % i), i))
         .min_by_key(|(extra, _)| *extra)
-        .unwrap();
-
-    time * bus
-}
-
-fn chinese_remainder_theorem(eqns: Vec<(u64, u64)>) -> u64 {
-    let mut it = eqns.iter();
-    it.next();
-    crt_internal(eqns[0].0, eqns[0].1, it)
+        .unwrap()
 }
 
-fn crt_internal(start: u64, iter: u64, mut remaining: Iter<'_, (u64, u64)>) -> u64 {
-    match remaining.next() {
-        Some((a, n)) => {
-            let mut check = start;
+#[aoc(day13, part1)]
+pub fn solve_p1((target, buses): &(u64, Vec<Option<u64>>)) -> Answer {
+    let (time, bus) = earliest(*target, buses);
 
-            while check % n != *a {
-                check += iter;
-            }
-            crt_internal(check, iter * n, remaining)
-        }
-        None => start,
-    }
+    checked::mul(13, "wait product", time, bus).into()
 }
 
-#[aoc(day13, part2)]
-pub fn solve_p2((_, buses): &(u64, Vec<Option<u64>>)) -> u64 {
-    let mut eqns = buses
+/// Bus `bus` leaving `ind` minutes after `t` means `t = remainder (mod bus)`, as
+/// `(remainder, bus)` pairs in the order the buses are listed.
+fn congruences(buses: &[Option<u64>]) -> Vec<(u64, u64)> {
+    buses
         .iter()
         .enumerate()
         .filter_map(|(ind, v)| v.map(|bus| ((bus - (ind as u64 % bus)) % bus, bus)))
-        .collect::<Vec<_>>();
-    eqns.sort_by_cached_key(|(_, x)| -(*x as i64));
-    // println!("{:?}", eqns);
+        .collect()
+}
+
+#[aoc(day13, part2)]
+pub fn solve_p2((_, buses): &(u64, Vec<Option<u64>>)) -> Answer {
+    let eqns = congruences(buses);
+    trace!(13, "congruences", eqns);
+
+    match number_theory::crt::<Exact>(&eqns) {
+        Ok((timestamp, _)) => timestamp.into(),
+        Err(CrtError::NoSolution) => panic!("the buses never line up"),
+        Err(CrtError::Overflow) => checked::expect(13, "timestamp", None),
+    }
+}
 
-    chinese_remainder_theorem(eqns)
+fn explain_p1((target, buses): &(u64, Vec<Option<u64>>)) -> Vec<Step> {
+    let (time, bus) = earliest(*target, buses);
+    vec![
+        Step::new(format!("the first bus to leave after {}", target))
+            .fact("bus", bus)
+            .fact("wait", format!("{} minutes", time)),
+    ]
+}
+
+fn explain_p2((_, buses): &(u64, Vec<Option<u64>>)) -> Vec<Step> {
+    let system = congruences(buses).into_iter().fold(
+        Step::new("each bus leaving its offset after t, as a congruence"),
+        |step, (remainder, bus)| {
+            step.fact(
+                format!("bus {}", bus),
+                format!("t = {} (mod {})", remainder, bus),
+            )
+        },
+    );
+
+    vec![
+        system,
+        Step::new("solved them together with the Chinese remainder theorem"),
+    ]
+}
+
+pub struct Day13;
+
+impl Puzzle for Day13 {
+    const DAY: u8 = 13;
+
+    type Input = (u64, Vec<Option<u64>>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
+
+    fn explain(input: &Self::Input, part: u8) -> Option<Vec<Step>> {
+        match part {
+            1 => Some(explain_p1(input)),
+            2 => Some(explain_p2(input)),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -62,27 +134,27 @@ mod tests {
     #[test]
     fn it_works() {
         let input = "939\n7,13,x,x,59,x,31,19";
-        let parsed = crate::day13::input_generator(input);
+        let parsed = crate::day13::input_generator(input).unwrap();
         assert_eq!(crate::day13::solve_p2(&parsed), 1068781);
 
         let input = "939\n17,x,13,19";
-        let parsed = crate::day13::input_generator(input);
+        let parsed = crate::day13::input_generator(input).unwrap();
         assert_eq!(crate::day13::solve_p2(&parsed), 3417);
 
         let input = "939\n67,7,59,61";
-        let parsed = crate::day13::input_generator(input);
+        let parsed = crate::day13::input_generator(input).unwrap();
         assert_eq!(crate::day13::solve_p2(&parsed), 754018);
 
         let input = "939\n67,x,7,59,61";
-        let parsed = crate::day13::input_generator(input);
+        let parsed = crate::day13::input_generator(input).unwrap();
         assert_eq!(crate::day13::solve_p2(&parsed), 779210);
 
         let input = "939\n67,7,x,59,61";
-        let parsed = crate::day13::input_generator(input);
+        let parsed = crate::day13::input_generator(input).unwrap();
         assert_eq!(crate::day13::solve_p2(&parsed), 1261476);
 
         let input = "939\n1789,37,47,1889";
-        let parsed = crate::day13::input_generator(input);
+        let parsed = crate::day13::input_generator(input).unwrap();
         assert_eq!(crate::day13::solve_p2(&parsed), 1202161486);
     }
 }
@@ -1,32 +1,42 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, io::BufRead};
 
 use aoc_runner_derive::{aoc, aoc_generator};
-use nom::{branch::alt, bytes::complete::is_a};
+use nom::{
+    branch::alt,
+    bytes::complete::is_a,
+    character::complete::digit1,
+    combinator::{all_consuming, map_res, verify},
+};
 use nom::{bytes::complete::tag, IResult};
 
+use crate::answer::Answer;
+use crate::checked;
+use crate::error::{Error, ParseError};
+use crate::puzzle::Puzzle;
+use crate::stream;
+
 #[derive(Debug, Clone)]
 pub enum Op {
     Mask(HashMap<u8, u8>),
     Set(usize, u64),
 }
 
+fn parse_line(line: &str) -> Result<Op, ParseError> {
+    all_consuming(alt((as_set, as_mask)))(line)
+        .map(|(_, op)| op)
+        .map_err(|err| {
+            ParseError::from_nom(line, err, "`mask = <36 bits>` or `mem[<addr>] = <value>`")
+        })
+}
+
 #[aoc_generator(day14)]
-fn input_generator(input: &str) -> Vec<Op> {
-    input
-        .lines()
-        .map(|e| alt((as_set, as_mask))(e))
-        .collect::<Vec<_>>()
-        .into_iter()
-        .collect::<Result<Vec<_>, _>>()
-        .unwrap()
-        .iter()
-        .map(|(_, op)| op.clone())
-        .collect()
+pub fn input_generator(input: &str) -> Result<Vec<Op>, ParseError> {
+    stream::lines(input, parse_line)
 }
 
 fn as_mask(input: &str) -> IResult<&str, Op> {
     let (input, _) = tag("mask = ")(input)?;
-    let (input, mask) = is_a("01X")(input)?;
+    let (input, mask) = verify(is_a("01X"), |mask: &str| mask.len() == 36)(input)?;
     let mask = mask
         .bytes()
         .rev()
@@ -43,11 +53,11 @@ fn as_mask(input: &str) -> IResult<&str, Op> {
 
 fn as_set(input: &str) -> IResult<&str, Op> {
     let (input, _) = tag("mem[")(input)?;
-    let (input, loc) = is_a("0123456789")(input)?;
+    let (input, loc) = map_res(digit1, |s: &str| s.parse())(input)?;
     let (input, _) = tag("] = ")(input)?;
-    let (input, num) = is_a("0123456789")(input)?;
+    let (input, num) = map_res(digit1, |s: &str| s.parse())(input)?;
 
-    Ok((input, Op::Set(loc.parse().unwrap(), num.parse().unwrap())))
+    Ok((input, Op::Set(loc, num)))
 }
 
 fn apply_mask(val: u64, mask: &HashMap<u8, u8>) -> u64 {
@@ -63,9 +73,9 @@ fn mask_base(val: usize, mask: &HashMap<u8, u8>) -> usize {
 }
 
 fn apply_mask2(val: usize, mask: &HashMap<u8, u8>) -> Vec<usize> {
-    let count = 35 - mask.len();
+    let count = 36 - mask.len();
     let base = mask_base(val, mask);
-    (0..2 << count)
+    (0..1 << count)
         .map(|filler| {
             (0..36)
                 .filter(|v| !mask.contains_key(v))
@@ -78,22 +88,22 @@ fn apply_mask2(val: usize, mask: &HashMap<u8, u8>) -> Vec<usize> {
 }
 
 #[aoc(day14, part1)]
-pub fn solve_p1(input: &[Op]) -> u64 {
+pub fn solve_p1(input: &[Op]) -> Answer {
     let mut mask = &Default::default();
     let mut mem: HashMap<usize, u64> = Default::default();
 
     input.iter().for_each(|op| match op {
         Op::Mask(inner) => mask = inner,
         Op::Set(ind, val) => {
-            mem.insert(*ind, apply_mask(*val, &mask));
+            mem.insert(*ind, apply_mask(*val, mask));
         }
     });
 
-    mem.iter().map(|(_, b)| b).sum()
+    checked::sum(14, "memory sum", mem.values().copied()).into()
 }
 
 #[aoc(day14, part2)]
-pub fn solve_p2(input: &[Op]) -> u64 {
+pub fn solve_p2(input: &[Op]) -> Answer {
     let mut mask = &Default::default();
     let mut mem: HashMap<usize, u64> = Default::default();
 
@@ -104,7 +114,31 @@ pub fn solve_p2(input: &[Op]) -> u64 {
         }),
     });
 
-    mem.iter().map(|(_, b)| b).sum()
+    checked::sum(14, "memory sum", mem.values().copied()).into()
+}
+
+pub struct Day14;
+
+impl Puzzle for Day14 {
+    const DAY: u8 = 14;
+
+    type Input = Vec<Op>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn read(reader: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        stream::read_lines(reader, parse_line)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
 }
 
 #[cfg(test)]
@@ -113,7 +147,7 @@ mod test {
     fn test_p1() {
         let input =
             "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X\nmem[8] = 11\nmem[7] = 101\nmem[8] = 0";
-        let parsed = crate::day14::input_generator(input);
+        let parsed = crate::day14::input_generator(input).unwrap();
         assert_eq!(crate::day14::solve_p1(&parsed), 165);
     }
 
@@ -121,7 +155,7 @@ mod test {
     fn test_p2() {
         let input =
             "mask = 000000000000000000000000000000X1001X\nmem[42] = 100\nmask = 00000000000000000000000000000000X0XX\nmem[26] = 1";
-        let parsed = crate::day14::input_generator(input);
+        let parsed = crate::day14::input_generator(input).unwrap();
         assert_eq!(crate::day14::solve_p2(&parsed), 208);
     }
 }
@@ -2,32 +2,71 @@ use std::collections::HashMap;
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::answer::Answer;
+use crate::context::Ticker;
+use crate::error::{parse_at, ParseError};
+use crate::puzzle::{Implementation, Puzzle};
+
 #[aoc_generator(day15)]
-pub fn input_generator(input: &str) -> HashMap<usize, usize> {
+pub fn input_generator(input: &str) -> Result<Vec<usize>, ParseError> {
     input
         .split(',')
-        .enumerate()
-        .map(|(i, u)| (u.parse().unwrap(), i + 1))
+        .map(|u| parse_at(input, u, "a starting number"))
         .collect()
 }
 
 #[aoc(day15, part1)]
-pub fn solve_p1(input: &HashMap<usize, usize>) -> usize {
-    nth_spoken_number(input, 2020)
+pub fn solve_p1(input: &[usize]) -> Answer {
+    nth_spoken_number(input, 2020).into()
 }
 
 #[aoc(day15, part2)]
-pub fn solve_p2(input: &HashMap<usize, usize>) -> usize {
-    nth_spoken_number(input, 30000000)
+pub fn solve_p2(input: &[usize]) -> Answer {
+    nth_spoken_number(input, 30000000).into()
+}
+
+fn nth_spoken_number(seed: &[usize], count: usize) -> usize {
+    if count <= seed.len() {
+        return seed[count - 1];
+    }
+    let (&last, earlier) = seed.split_last().unwrap();
+    let last_turn = seed.len();
+    let mut latest = last;
+
+    // the turn each number was last spoken on, not counting the latest; 0 for never
+    let size = count.max(seed.iter().copied().max().unwrap() + 1);
+    let mut log = vec![0u32; size];
+    for (turn, &number) in earlier.iter().enumerate() {
+        log[number] = turn as u32 + 1;
+    }
+
+    let mut ticker = Ticker::new(15, Some(count.saturating_sub(last_turn) as u64));
+    for turn in last_turn..count {
+        ticker.tick();
+        let next = match log[latest] {
+            0 => 0,
+            spoken => turn - spoken as usize,
+        };
+        log[latest] = turn as u32;
+        latest = next;
+    }
+
+    latest
 }
 
-fn nth_spoken_number(seed: &HashMap<usize, usize>, count: usize) -> usize {
-    let mut seed_entries = seed.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>();
-    seed_entries.sort_by_cached_key(|(_, v)| -(*v as isize));
-    let mut latest_entry = seed_entries[0];
-    let mut log: HashMap<_, _> = seed_entries.iter().skip(1).copied().collect();
+fn hash_log(seed: &[usize], count: usize) -> usize {
+    if count <= seed.len() {
+        return seed[count - 1];
+    }
+    let (&last, earlier) = seed.split_last().unwrap();
+    let mut latest_entry = (last, seed.len());
+    let mut log: HashMap<_, _> = earlier
+        .iter()
+        .enumerate()
+        .map(|(turn, &number)| (number, turn + 1))
+        .collect();
 
-    let mut i = seed.iter().map(|(_, v)| *v).max().unwrap() + 1;
+    let mut i = seed.len() + 1;
 
     while i <= count {
         let new_num = match log.get(&latest_entry.0) {
@@ -43,12 +82,47 @@ fn nth_spoken_number(seed: &HashMap<usize, usize>, count: usize) -> usize {
     latest_entry.0
 }
 
+pub struct Day15;
+
+impl Puzzle for Day15 {
+    const DAY: u8 = 15;
+
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn implementations() -> Vec<Implementation<Self::Input>> {
+        vec![
+            Implementation {
+                part: 1,
+                name: "hash-log",
+                solve: |input| hash_log(input, 2020).into(),
+            },
+            Implementation {
+                part: 2,
+                name: "hash-log",
+                solve: |input| hash_log(input, 30000000).into(),
+            },
+        ]
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use super::nth_spoken_number;
+    use super::{hash_log, nth_spoken_number};
 
     fn test_wrapper(input: &str, count: usize) -> usize {
-        let seed = crate::day15::input_generator(input);
+        let seed = crate::day15::input_generator(input).unwrap();
         nth_spoken_number(&seed, count)
     }
     #[test]
@@ -60,6 +134,16 @@ mod test {
         assert_eq!(test_wrapper("2,3,1", 2020), 78);
         assert_eq!(test_wrapper("3,2,1", 2020), 438);
         assert_eq!(test_wrapper("3,1,2", 2020), 1836);
+
+        // more starting numbers than turns leaves nothing to count down
+        let seed: Vec<_> = (0..3000).map(|n| (n * 7 % 3000).to_string()).collect();
+        assert_eq!(test_wrapper(&seed.join(","), 2020), 2019 * 7 % 3000);
+        assert_eq!(test_wrapper("0,3,6", 2), 3);
+        assert_eq!(hash_log(&[0, 3, 6], 2), 3);
+
+        // a starting number that repeats
+        assert_eq!(test_wrapper("1,1", 3), 1);
+        assert_eq!(hash_log(&[1, 1], 3), 1);
     }
 
     #[test]
@@ -1,21 +1,28 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     ops::RangeInclusive,
 };
 
 use aoc_runner_derive::{aoc, aoc_generator};
+use itertools::Itertools;
 use nom::{
     bytes::complete::{is_a, tag},
+    character::complete::digit1,
+    combinator::{all_consuming, map_res},
     IResult,
 };
 
+use crate::answer::Answer;
+use crate::assignment;
+use crate::checked;
+use crate::error::{parse_at, ParseError};
+use crate::explain::Step;
+use crate::puzzle::Puzzle;
+
 fn parse_range(input: &str) -> IResult<&str, RangeInclusive<u16>> {
-    let (input, start) = is_a("0123456789")(input)?;
+    let (input, start) = map_res(digit1, |s: &str| s.parse())(input)?;
     let (input, _) = tag("-")(input)?;
-    let (input, end) = is_a("0123456789")(input)?;
-
-    let start = start.parse().unwrap();
-    let end = end.parse().unwrap();
+    let (input, end) = map_res(digit1, |s: &str| s.parse())(input)?;
 
     Ok((input, start..=end))
 }
@@ -39,80 +46,114 @@ fn parse_field(input: &str) -> IResult<&str, Field> {
     Ok((input, (name.to_owned(), (first, second))))
 }
 
+fn parse_ticket(input: &str, line: &str) -> Result<Vec<u16>, ParseError> {
+    line.split(',')
+        .map(|i| parse_at(input, i, "a ticket value"))
+        .collect()
+}
+
 #[aoc_generator(day16)]
-pub fn input_generator(input: &str) -> ParsedInput {
-    let pieces: Vec<_> = input.split("\n\n").collect();
-    let fields = pieces[0]
+pub fn input_generator(input: &str) -> Result<ParsedInput, ParseError> {
+    let mut pieces = input.split("\n\n");
+    let fields = pieces
+        .next()
+        .unwrap_or_default()
         .lines()
-        .map(|line| parse_field(line).map(|(_, stuff)| stuff))
-        .collect::<Vec<_>>()
-        .into_iter()
-        .collect::<Result<HashMap<_, _>, _>>()
-        .unwrap();
-    let your_ticket = pieces[1]
-        .split_once('\n')
-        .and_then(|(_, b)| {
-            b.split(',')
-                .map(|i| i.parse())
-                .collect::<Vec<_>>()
-                .into_iter()
-                .collect::<Result<Vec<_>, _>>()
-                .ok()
+        .map(|line| {
+            all_consuming(parse_field)(line)
+                .map(|(_, stuff)| stuff)
+                .map_err(|e| ParseError::from_nom(input, e, "a field like `class: 1-3 or 5-7`"))
         })
-        .unwrap();
+        .collect::<Result<HashMap<_, _>, _>>()?;
 
-    let other_tickets = pieces[2]
-        .lines()
-        .skip(1)
-        .map(|e| {
-            e.split(',')
-                .map(|i| i.parse())
-                .collect::<Vec<_>>()
-                .into_iter()
-                .collect::<Result<Vec<_>, _>>()
+    let your_ticket = pieces
+        .next()
+        .ok_or_else(|| ParseError::end_of(input, "a `your ticket:` section"))?;
+    let your_ticket = match your_ticket.split_once('\n') {
+        Some(("your ticket:", line)) => parse_ticket(input, line)?,
+        _ => {
+            return Err(ParseError::at(
+                input,
+                your_ticket,
+                "`your ticket:` and one ticket",
+            ))
+        }
+    };
+
+    let nearby = pieces
+        .next()
+        .ok_or_else(|| ParseError::end_of(input, "a `nearby tickets:` section"))?;
+    let mut lines = nearby.lines();
+    if lines.next() != Some("nearby tickets:") {
+        return Err(ParseError::at(input, nearby, "`nearby tickets:`"));
+    }
+
+    let other_tickets = lines
+        .map(|line| {
+            let ticket = parse_ticket(input, line)?;
+            if ticket.len() == your_ticket.len() {
+                Ok(ticket)
+            } else {
+                Err(ParseError::at(
+                    input,
+                    line,
+                    format!("a ticket with {} values", your_ticket.len()),
+                ))
+            }
         })
-        .collect::<Vec<_>>()
-        .into_iter()
-        .collect::<Result<Vec<_>, _>>()
-        .unwrap();
+        .collect::<Result<Vec<_>, _>>()?;
 
-    (fields, your_ticket, other_tickets)
+    Ok((fields, your_ticket, other_tickets))
 }
 
 fn validate(
     ticket: &[u16],
     ranges: &HashMap<String, (RangeInclusive<u16>, RangeInclusive<u16>)>,
-) -> u16 {
-    ticket.iter().fold(0, |acc, e| {
-        acc + if !ranges
+) -> u64 {
+    checked::sum(
+        16,
+        "error rate",
+        ticket
             .iter()
-            .any(|(_, (low, high))| low.contains(e) || high.contains(e))
-        {
-            *e
-        } else {
-            0
-        }
-    })
+            .filter(|e| {
+                !ranges
+                    .iter()
+                    .any(|(_, (low, high))| low.contains(e) || high.contains(e))
+            })
+            .map(|&e| e as u64),
+    )
 }
 
 #[aoc(day16, part1)]
-pub fn solve_p1((ranges, _, other_tickets): &ParsedInput) -> u16 {
-    // println!("{:?}", ranges);
-    // println!("{:?}", other_tickets);
+pub fn solve_p1((ranges, _, other_tickets): &ParsedInput) -> Answer {
+    trace!(
+        16,
+        "input",
+        fields = ranges.len(),
+        tickets = other_tickets.len()
+    );
 
-    other_tickets
-        .iter()
-        .fold(0, |acc, ticket| acc + validate(ticket, ranges))
+    checked::sum(
+        16,
+        "error rate",
+        other_tickets.iter().map(|ticket| validate(ticket, ranges)),
+    )
+    .into()
 }
 
-#[aoc(day16, part2)]
-pub fn solve_p2((ranges, your_ticket, other_tickets): &ParsedInput) -> u64 {
-    let other_tickets = other_tickets
+fn valid_tickets((ranges, _, other_tickets): &ParsedInput) -> Vec<&Vec<u16>> {
+    other_tickets
         .iter()
-        .filter(|ticket| validate(*ticket, ranges) == 0)
-        .collect::<Vec<_>>();
+        .filter(|ticket| validate(ticket, ranges) == 0)
+        .collect()
+}
 
-    let mut map = ranges
+/// The columns each field's ranges fit on every valid ticket.
+fn candidates<'a>(
+    (ranges, your_ticket, _): &'a ParsedInput,
+    other_tickets: &[&Vec<u16>],
+) -> HashMap<&'a String, HashSet<usize>> {
+    ranges
         .iter()
         .map(|(name, (first, second))| {
             (
@@ -126,53 +167,131 @@ pub fn solve_p2((ranges, your_ticket, other_tickets): &ParsedInput) -> u64 {
                     .collect::<HashSet<_>>(),
             )
         })
-        .collect::<HashMap<_, _>>();
-
-    let reference = map.clone();
-
-    map.iter_mut()
-        .filter(|(_, candidates)| candidates.len() > 1)
-        .for_each(|(_, candidates)| {
-            let len = candidates.len();
-            reference
-                .iter()
-                .filter(|(_, stuffs)| stuffs.len() + 1 == len)
-                .for_each(|(_, smaller_set)| {
-                    smaller_set.iter().for_each(|col| {
-                        candidates.remove(col);
-                    })
-                })
-        });
+        .collect()
+}
+
+/// The one column each field can be in once the others have taken theirs.
+fn assign(candidates: &HashMap<&String, HashSet<usize>>) -> BTreeMap<String, usize> {
+    assignment::solve(candidates)
+        .unwrap_or_else(|err| panic!("fields can't be matched to columns: {}", err))
+        .into_iter()
+        .map(|(name, column)| (name.clone(), column))
+        .collect()
+}
+
+#[aoc(day16, part2)]
+pub fn solve_p2(input: &ParsedInput) -> Answer {
+    let your_ticket = &input.1;
+    let map = assign(&candidates(input, &valid_tickets(input)));
 
-    map.iter()
+    let departures = map
+        .iter()
         .filter(|(name, _)| name.starts_with("departure"))
-        .flat_map(|(_, set)| set.iter())
-        .copied()
-        .map(|ind| your_ticket[ind] as u64)
-        .product()
+        .map(|(_, ind)| your_ticket[*ind] as u64);
+    checked::product(16, "departure product", departures).into()
+}
+
+fn explain_p2(input: &ParsedInput) -> Vec<Step> {
+    let your_ticket = &input.1;
+    let valid = valid_tickets(input);
+    let candidates = candidates(input, &valid);
+
+    let mut fields = candidates.iter().collect::<Vec<_>>();
+    fields.sort_by_key(|(name, columns)| (columns.len(), **name));
+    let narrowed = fields.iter().fold(
+        Step::new("columns whose values all fit each field's ranges"),
+        |step, (name, columns)| {
+            let mut columns = columns.iter().collect::<Vec<_>>();
+            columns.sort_unstable();
+            step.fact(name, columns.iter().join(", "))
+        },
+    );
+
+    let mut map = assign(&candidates)
+        .into_iter()
+        .map(|(name, column)| (column, name))
+        .collect::<Vec<_>>();
+    map.sort_unstable();
+    let columns = map.iter().fold(
+        Step::new("each field's column, once the others have taken theirs"),
+        |step, (column, name)| {
+            step.fact(
+                name,
+                format!("column {}, {} on your ticket", column, your_ticket[*column]),
+            )
+        },
+    );
+
+    vec![
+        Step::new(format!(
+            "kept the {} of {} nearby tickets with no invalid values",
+            valid.len(),
+            input.2.len()
+        )),
+        narrowed,
+        columns,
+        Step::new("multiplied your values for the fields starting with `departure`"),
+    ]
+}
+
+pub struct Day16;
+
+impl Puzzle for Day16 {
+    const DAY: u8 = 16;
+
+    type Input = ParsedInput;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
+
+    fn explain(input: &Self::Input, part: u8) -> Option<Vec<Step>> {
+        match part {
+            2 => Some(explain_p2(input)),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    fn p1_wrapper(input: &str) -> u16 {
-        let parsed = input_generator(input);
+    fn p1_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p1(&parsed)
     }
 
-    fn p2_wrapper(input: &str) -> u64 {
-        let parsed = input_generator(input);
+    fn p2_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p2(&parsed)
     }
 
     #[test]
     fn test_p1() {
         assert_eq!(p1_wrapper("class: 1-3 or 5-7\nrow: 6-11 or 33-44\nseat: 13-40 or 45-50\n\nyour ticket:\n7,1,14\n\nnearby tickets:\n7,3,47\n40,4,50\n55,2,20\n38,6,12"), 71);
+
+        // more than a u16 of invalid values
+        let nearby = vec!["999"; 100].join("\n");
+        let input = format!(
+            "class: 1-3 or 5-7\n\nyour ticket:\n1\n\nnearby tickets:\n{}",
+            nearby
+        );
+        assert_eq!(p1_wrapper(&input), 99900);
     }
 
     #[test]
     fn test_p2() {
         assert_eq!(p2_wrapper("class: 1-3 or 5-7\nrow: 6-11 or 33-44\nseat: 13-40 or 45-50\n\nyour ticket:\n7,1,14\n\nnearby tickets:\n7,3,47\n40,4,50\n55,2,20\n38,6,12"), 1);
+        // two fields settled at once, leaving a departure field with three candidates
+        assert_eq!(p2_wrapper("zone: 1-1 or 50-50\nrow: 2-2 or 50-50\ndeparture time: 1-3 or 50-50\n\nyour ticket:\n1,2,3\n\nnearby tickets:\n1,2,3"), 3);
     }
 }
@@ -1,177 +1,155 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashSet;
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::answer::Answer;
+use crate::automaton::{Automaton, BirthSurvive, Lattice, Rule, Topology};
+use crate::error::ParseError;
+use crate::frames::{history, Frame};
+use crate::grid::Grid;
+use crate::puzzle::Puzzle;
+
 #[aoc_generator(day17)]
-pub fn input_generator(input: &str) -> HashSet<(i8, i8, i8)> {
+pub fn input_generator(input: &str) -> Result<HashSet<(i8, i8, i8)>, ParseError> {
     input
         .lines()
         .enumerate()
         .flat_map(|(y, line)| {
-            line.chars().enumerate().filter_map(move |(x, chr)| {
-                if chr == '.' {
-                    None
-                } else {
-                    Some((x as i8, y as i8, 0))
-                }
-            })
+            line.char_indices()
+                .enumerate()
+                .filter_map(move |(x, (i, chr))| match chr {
+                    '.' => None,
+                    '#' => Some(Ok((x as i8, y as i8, 0))),
+                    _ => Some(Err(ParseError::at(input, &line[i..], "`#` or `.`"))),
+                })
         })
         .collect()
 }
 
-fn step(state: &HashSet<(i8, i8, i8)>) -> HashSet<(i8, i8, i8)> {
-    let mut nearby: HashMap<(i8, i8, i8), u8> = HashMap::new();
-    state.iter().for_each(|point| {
-        (0..27)
-            .filter(|i| *i != 13)
-            .map(move |e| {
-                (
-                    point.0 - 1 + (e % 3),
-                    point.1 - 1 + (e / 3 % 3),
-                    point.2 - 1 + (e / 9),
-                )
-            })
-            .for_each(|point| {
-                let counter = nearby.entry(point).or_insert(0);
-                *counter += 1;
-            });
-    });
-
-    nearby
-        .iter()
-        .filter_map(|(point, count)| {
-            if state.contains(point) {
-                if (2..=3).contains(count) {
-                    Some(point)
-                } else {
-                    None
-                }
-            } else if *count == 3 {
-                Some(point)
-            } else {
-                None
-            }
-        })
-        .copied()
-        .collect()
-}
+const CONWAY: BirthSurvive = BirthSurvive {
+    birth: &[3],
+    survive: &[2, 3],
+};
 
-fn render(state: &HashSet<(i8, i8, i8)>) {
-    let min_x = state.iter().min_by_key(|(x, _, _)| x).unwrap();
-    let max_x = state.iter().max_by_key(|(x, _, _)| x).unwrap();
-    let min_y = state.iter().min_by_key(|(_, y, _)| y).unwrap();
-    let max_y = state.iter().max_by_key(|(_, y, _)| y).unwrap();
-    let min_z = state.iter().min_by_key(|(_, _, z)| z).unwrap();
-    let max_z = state.iter().max_by_key(|(_, _, z)| z).unwrap();
-
-    let mut out: String = format!(
-        "x: {} -> {}\ny: {} -> {}\nz: {} -> {}\n",
-        min_x.0, max_x.0, min_y.1, max_y.1, min_z.2, max_z.2
-    );
-
-    for z in min_z.2..=max_z.2 {
-        out += &format!("z={}\n", z);
-        for y in min_y.1..=max_y.1 {
-            for x in min_x.0..=max_x.0 {
-                out += if state.contains(&(x, y, z)) { "#" } else { "." };
-            }
-            out += "\n";
-        }
-        out += "\n";
-    }
+#[aoc(day17, part1)]
+pub fn solve_p1(input: &HashSet<(i8, i8, i8)>) -> Answer {
+    let cubes = input
+        .iter()
+        .map(|&(x, y, z)| [x as i32, y as i32, z as i32]);
 
-    println!("{}", out);
+    boot(Automaton::new(Lattice::<3>, CONWAY, cubes))
 }
 
-#[aoc(day17, part1)]
-pub fn solve_p1(input: &HashSet<(i8, i8, i8)>) -> usize {
-    // println!("{:?}", input);
-    let mut state = input.clone();
+#[aoc(day17, part2)]
+pub fn solve_p2(input: &HashSet<(i8, i8, i8)>) -> Answer {
+    let cubes = input
+        .iter()
+        .map(|&(x, y, z)| [x as i32, y as i32, z as i32, 0]);
 
-    // println!("0: {:?}", state.len());
-    // render(&state);
-    for _i in 0..6 {
-        state = step(&state);
+    boot(Automaton::new(Lattice::<4>, CONWAY, cubes))
+}
 
-        // println!("{}: {}", _i+1, state.len());
-        // render(&state);
+/// The six-cycle boot process.
+fn boot<T: Topology, R: Rule>(mut cubes: Automaton<T, R>) -> Answer {
+    for _ in 0..6 {
+        cubes.step();
+        trace!(
+            17,
+            "cycle",
+            generation = cubes.generation(),
+            active = cubes.population()
+        );
     }
-
-    state.len()
+    cubes.population().into()
 }
 
-fn step2(state: &HashSet<(i8, i8, i8, i8)>) -> HashSet<(i8, i8, i8, i8)> {
-    let mut nearby: HashMap<(i8, i8, i8, i8), u8> = HashMap::new();
-    state.iter().for_each(|point| {
-        (0..81)
-            .filter(|i| *i != 40)
-            .map(move |e| {
-                (
-                    point.0 - 1 + (e % 3),
-                    point.1 - 1 + (e / 3 % 3),
-                    point.2 - 1 + (e / 9 % 3),
-                    point.3 - 1 + (e / 27),
-                )
-            })
-            .for_each(|point| {
-                let counter = nearby.entry(point).or_insert(0);
-                *counter += 1;
-            });
-    });
-
-    nearby
+/// One frame per generation and z (and, in part 2, w) slice. Every frame covers the same
+/// x/y bounds, and every generation the same slices.
+pub fn frames(input: &HashSet<(i8, i8, i8)>, part: u8, generations: usize) -> Vec<Frame> {
+    let cubes = input
         .iter()
-        .filter_map(|(point, count)| {
-            if state.contains(point) {
-                if (2..=3).contains(count) {
-                    Some(point)
-                } else {
-                    None
-                }
-            } else if *count == 3 {
-                Some(point)
-            } else {
-                None
+        .map(|&(x, y, z)| [x as i32, y as i32, z as i32, 0]);
+    let history: Vec<HashSet<[i32; 4]>> = match part {
+        1 => history(
+            Automaton::new(Lattice::<3>, CONWAY, cubes.map(|[x, y, z, _]| [x, y, z])),
+            generations,
+        )
+        .into_iter()
+        .map(|live| live.into_iter().map(|[x, y, z]| [x, y, z, 0]).collect())
+        .collect(),
+        _ => history(Automaton::new(Lattice::<4>, CONWAY, cubes), generations),
+    };
+
+    let mut low = [i32::MAX; 4];
+    let mut high = [i32::MIN; 4];
+    for cube in history.iter().flatten() {
+        for axis in 0..4 {
+            low[axis] = low[axis].min(cube[axis]);
+            high[axis] = high[axis].max(cube[axis]);
+        }
+    }
+    if low[0] > high[0] {
+        return vec![];
+    }
+
+    let mut frames = vec![];
+    for (generation, live) in history.iter().enumerate() {
+        for z in low[2]..=high[2] {
+            for w in low[3]..=high[3] {
+                let name = match part {
+                    1 => format!("gen{:03}-z{}", generation, z),
+                    _ => format!("gen{:03}-z{}-w{}", generation, z, w),
+                };
+                let height = (high[1] - low[1] + 1) as usize;
+                let width = (high[0] - low[0] + 1) as usize;
+                let image = Grid::from_fn(height, width, |(row, col)| {
+                    live.contains(&[low[0] + col as i32, low[1] + row as i32, z, w]) as u8
+                });
+
+                frames.push(Frame {
+                    name,
+                    generation,
+                    image,
+                    palette: &['.', '#'],
+                });
             }
-        })
-        .copied()
-        .collect()
+        }
+    }
+
+    frames
 }
 
-#[aoc(day17, part2)]
-pub fn solve_p2(input: &HashSet<(i8, i8, i8)>) -> usize {
-    let mut state = input.iter().map(|(x, y, z)| (*x, *y, *z, 0)).collect();
-    assert_eq!(
-        (0..81)
-            .map(|i| (
-                -1 + (i % 3),
-                -1 + (i / 3 % 3),
-                -1 + (i / 9 % 3),
-                -1 + (i / 27)
-            ))
-            .collect::<HashSet<_>>()
-            .len(),
-        81
-    );
-
-    for _i in 0..6 {
-        state = step2(&state);
+pub struct Day17;
+
+impl Puzzle for Day17 {
+    const DAY: u8 = 17;
+
+    type Input = HashSet<(i8, i8, i8)>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
     }
 
-    state.len()
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    fn p1_wrapper(input: &str) -> usize {
-        let parsed = input_generator(input);
+    fn p1_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p1(&parsed)
     }
 
-    fn p2_wrapper(input: &str) -> usize {
-        let parsed = input_generator(input);
+    fn p2_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p2(&parsed)
     }
 
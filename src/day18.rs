@@ -1,5 +1,20 @@
+use std::{fmt::Display, io::BufRead};
+
 use aoc_runner_derive::{aoc, aoc_generator};
-use nom::{branch::alt, bytes::complete::tag, character::complete::digit1, IResult};
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::digit1,
+    combinator::{all_consuming, map_res},
+    IResult,
+};
+
+use crate::answer::Answer;
+use crate::bigint::{Exact, Natural};
+use crate::checked;
+use crate::error::{Error, ParseError};
+use crate::puzzle::Puzzle;
+use crate::stream;
 
 #[derive(Debug, Clone)]
 pub enum Token {
@@ -15,21 +30,37 @@ impl Default for Token {
     }
 }
 
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Val(v) => write!(f, "{}", v),
+            Token::Add => f.write_str("+"),
+            Token::Mult => f.write_str("*"),
+            Token::Paren(eqn) => write!(f, "({})", expression(eqn)),
+        }
+    }
+}
+
+/// The tokens written back out the way the homework has them.
+fn expression(eqn: &[Token]) -> String {
+    eqn.iter()
+        .map(Token::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 fn parse_val(input: &str) -> IResult<&str, Token> {
-    let (input, v) = digit1(input)?;
-    // println!("found val: {:?}", input);
-    Ok((input, Token::Val(v.parse().unwrap())))
+    let (input, v) = map_res(digit1, |s: &str| s.parse())(input)?;
+    Ok((input, Token::Val(v)))
 }
 
 fn parse_add(input: &str) -> IResult<&str, Token> {
     let (input, _) = tag(" + ")(input)?;
-    // println!("found add: {:?}", input);
     Ok((input, Token::Add))
 }
 
 fn parse_mult(input: &str) -> IResult<&str, Token> {
     let (input, _) = tag(" * ")(input)?;
-    // println!("found mult: {:?}", input);
     Ok((input, Token::Mult))
 }
 
@@ -37,45 +68,46 @@ fn parse_paren(input: &str) -> IResult<&str, Token> {
     let (input, _) = tag("(")(input)?;
     let (input, inner) = parse_eqn(input)?;
     let (input, _) = tag(")")(input)?;
-
-    // println!("found_paren: {:?}", input);
-
     Ok((input, Token::Paren(inner)))
 }
 
 fn parse_eqn(input: &str) -> IResult<&str, Vec<Token>> {
-    let mut input = input;
-    let mut tokens = Vec::new();
+    let (mut input, first) = alt((parse_val, parse_paren))(input)?;
+    let mut tokens = vec![first];
 
     while !input.is_empty() && !input.starts_with(')') {
-        let (rest, tok) = alt((parse_val, parse_add, parse_mult, parse_paren))(input)?;
+        let (rest, op) = alt((parse_add, parse_mult))(input)?;
+        let (rest, tok) = alt((parse_val, parse_paren))(rest)?;
         input = rest;
+        tokens.push(op);
         tokens.push(tok);
     }
 
     Ok((input, tokens))
 }
 
-fn solve(input: &[Token]) -> usize {
-    let mut state = 0;
+fn solve<N: Natural>(input: &[Token]) -> N {
+    let mut state = N::default();
     let mut pos = 0;
     while pos < input.len() {
         match input[pos] {
-            Token::Val(v) => state = v,
+            Token::Val(v) => state = N::from(v as u64),
             Token::Add => {
-                state += match input[pos + 1].clone() {
-                    Token::Val(v) => v,
+                let rhs = match input[pos + 1].clone() {
+                    Token::Val(v) => N::from(v as u64),
                     Token::Paren(ref eqn) => solve(eqn),
                     _ => unreachable!(),
                 };
+                state = checked::add(18, "addition", state, rhs);
                 pos += 1;
             }
             Token::Mult => {
-                state *= match input[pos + 1] {
-                    Token::Val(v) => v,
+                let rhs = match input[pos + 1] {
+                    Token::Val(v) => N::from(v as u64),
                     Token::Paren(ref eqn) => solve(eqn),
                     _ => unreachable!(),
                 };
+                state = checked::mul(18, "multiplication", state, rhs);
                 pos += 1;
             }
             Token::Paren(ref eqn) => state = solve(eqn),
@@ -86,25 +118,25 @@ fn solve(input: &[Token]) -> usize {
     state
 }
 
-fn solve2(input: &[Token]) -> usize {
+fn solve2<N: Natural>(input: &[Token]) -> N {
     let mut state = Vec::new();
 
     input.iter().for_each(|tok| match tok {
-        Token::Val(v) => state.push(*v),
+        Token::Val(v) => state.push(N::from(*v as u64)),
         Token::Add => {
             let first = state.pop().unwrap();
             let second = state.pop().unwrap();
-            state.push(first + second);
+            state.push(checked::add(18, "addition", first, second));
         }
         Token::Mult => {
             let first = state.pop().unwrap();
             let second = state.pop().unwrap();
-            state.push(first * second);
+            state.push(checked::mul(18, "multiplication", first, second));
         }
         _ => unreachable!(),
     });
 
-    state[0]
+    state.swap_remove(0)
 }
 
 enum Precedence {
@@ -161,36 +193,81 @@ fn shunting_yard(input: &[Token]) -> Vec<Token> {
     output
 }
 
+fn parse_line(line: &str) -> Result<Vec<Token>, ParseError> {
+    all_consuming(parse_eqn)(line)
+        .map(|(_, eqn)| eqn)
+        .map_err(|e| ParseError::from_nom(line, e, "a number, `+`, `*` or parentheses"))
+}
+
 #[aoc_generator(day18)]
-pub fn input_generator(input: &str) -> Vec<Vec<Token>> {
-    input
-        .lines()
-        .map(|line| parse_eqn(line).unwrap().1)
-        .collect()
+pub fn input_generator(input: &str) -> Result<Vec<Vec<Token>>, ParseError> {
+    stream::lines(input, parse_line)
 }
 
 #[aoc(day18, part1)]
-pub fn solve_p1(input: &[Vec<Token>]) -> usize {
-    // println!("{:?}", input);
-    input.iter().map(|op| solve(op)).sum()
+pub fn solve_p1(input: &[Vec<Token>]) -> Answer {
+    trace!(18, "input", expressions = input.len());
+    checked::sum(
+        18,
+        "sum of results",
+        input.iter().map(|eqn| {
+            let value = solve::<Exact>(eqn);
+            trace!(18, "evaluate", expression = expression(eqn), value);
+            value
+        }),
+    )
+    .into()
 }
 
 #[aoc(day18, part2)]
-pub fn solve_p2(input: &[Vec<Token>]) -> usize {
-    input.iter().map(|op| solve2(&shunting_yard(op))).sum()
+pub fn solve_p2(input: &[Vec<Token>]) -> Answer {
+    checked::sum(
+        18,
+        "sum of results",
+        input.iter().map(|eqn| {
+            let value = solve2::<Exact>(&shunting_yard(eqn));
+            trace!(18, "evaluate", expression = expression(eqn), value);
+            value
+        }),
+    )
+    .into()
+}
+
+pub struct Day18;
+
+impl Puzzle for Day18 {
+    const DAY: u8 = 18;
+
+    type Input = Vec<Vec<Token>>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn read(reader: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        stream::read_lines(reader, parse_line)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    fn p1_wrapper(input: &str) -> usize {
-        let parsed = input_generator(input);
+    fn p1_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p1(&parsed)
     }
 
-    fn p2_wrapper(input: &str) -> usize {
-        let parsed = input_generator(input);
+    fn p2_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p2(&parsed)
     }
 
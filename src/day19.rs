@@ -1,13 +1,23 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{
     branch::alt,
     bytes::complete::{tag, take, take_until},
     character::complete::digit1,
+    combinator::{all_consuming, map_res},
     IResult,
 };
 
+use crate::answer::Answer;
+use crate::context::Ticker;
+use crate::error::{Error, ParseError};
+use crate::puzzle::Puzzle;
+
+fn parse_id(input: &str) -> IResult<&str, u8> {
+    map_res(digit1, |s: &str| s.parse())(input)
+}
+
 type Sequence = Vec<u8>;
 
 #[derive(Debug, Clone)]
@@ -18,11 +28,11 @@ pub enum Rule {
 }
 
 fn parse_rule(input: &str) -> IResult<&str, (u8, Rule)> {
-    let (input, id) = digit1(input)?;
+    let (input, id) = parse_id(input)?;
     let (input, _) = tag(": ")(input)?;
     let (input, rule) = alt((parse_value, parse_either, parse_sequence))(input)?;
 
-    Ok((input, (id.parse().unwrap(), rule)))
+    Ok((input, (id, rule)))
 }
 
 fn parse_value(input: &str) -> IResult<&str, Rule> {
@@ -45,39 +55,111 @@ fn parse_either(input: &str) -> IResult<&str, Rule> {
 
 fn parse_sequence(input: &str) -> IResult<&str, Rule> {
     let mut output = Vec::new();
-    let (rest, num) = digit1(input)?;
+    let (rest, num) = parse_id(input)?;
 
     let mut input = rest;
-    output.push(num.parse().unwrap());
+    output.push(num);
 
     while !input.is_empty() && !input.starts_with(" | ") {
         let (rest, _) = tag(" ")(input)?;
-        let (rest, digit) = digit1(rest)?;
+        let (rest, digit) = parse_id(rest)?;
         input = rest;
-        output.push(digit.parse().unwrap());
+        output.push(digit);
     }
 
     Ok((input, Rule::Sequence(output)))
 }
 
+/// The rules `rule` can try first, before it has matched anything.
+fn first_rules(rule: &Rule) -> Vec<u8> {
+    match rule {
+        Rule::Value(_) => vec![],
+        Rule::Sequence(seq) => seq.first().copied().into_iter().collect(),
+        Rule::Either(left, right) => [first_rules(left), first_rules(right)].concat(),
+    }
+}
+
+/// Whether `id` can come back round to a rule on `path` without matching anything first,
+/// which would recurse forever. `done` holds the rules already known not to.
+fn left_recursive(
+    rules: &HashMap<u8, Rule>,
+    id: u8,
+    path: &mut Vec<u8>,
+    done: &mut HashSet<u8>,
+) -> bool {
+    if path.contains(&id) {
+        return true;
+    }
+    if done.contains(&id) {
+        return false;
+    }
+
+    path.push(id);
+    let recursive = first_rules(&rules[&id])
+        .into_iter()
+        .any(|first| left_recursive(rules, first, path, done));
+    path.pop();
+    done.insert(id);
+    recursive
+}
+
+/// Points at the first rule `text` refers to that `rules` doesn't define, or at the end of
+/// the rules if there's no rule 0 to start matching from, or at a rule that can start with
+/// itself.
+fn check_references(input: &str, text: &str, rules: &HashMap<u8, Rule>) -> Result<(), ParseError> {
+    if !rules.contains_key(&0) {
+        return Err(ParseError::at(input, &text[text.len()..], "rule 0"));
+    }
+
+    let definitions = text.lines().filter_map(|line| line.split_once(": "));
+    for reference in definitions.clone().flat_map(|(_, body)| body.split(' ')) {
+        if let Ok(id) = reference.parse::<u8>() {
+            if !rules.contains_key(&id) {
+                return Err(ParseError::at(input, reference, "a rule that is defined"));
+            }
+        }
+    }
+
+    let mut done = HashSet::new();
+    for (id, body) in definitions {
+        if let Ok(id) = id.parse() {
+            if left_recursive(rules, id, &mut vec![], &mut done) {
+                return Err(ParseError::at(
+                    input,
+                    body,
+                    "a rule that matches something before it repeats",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
 #[aoc_generator(day19)]
-pub fn input_generator(input: &str) -> (HashMap<u8, Rule>, Vec<String>) {
-    let parts = input.split("\n\n").collect::<Vec<_>>();
-    (
-        parts[0]
-            .lines()
-            .map(|line| parse_rule(line).unwrap().1)
-            .collect(),
-        parts[1].lines().map(|line| line.to_owned()).collect(),
-    )
+pub fn input_generator(input: &str) -> Result<(HashMap<u8, Rule>, Vec<String>), ParseError> {
+    let (text, messages) = input
+        .split_once("\n\n")
+        .ok_or_else(|| ParseError::end_of(input, "a blank line followed by messages"))?;
+    let rules = text
+        .lines()
+        .map(|line| {
+            all_consuming(parse_rule)(line)
+                .map(|(_, rule)| rule)
+                .map_err(|e| ParseError::from_nom(input, e, "a rule like `0: 4 1 | 5`"))
+        })
+        .collect::<Result<_, _>>()?;
+    check_references(input, text, &rules)?;
+
+    Ok((
+        rules,
+        messages.lines().map(|line| line.to_owned()).collect(),
+    ))
 }
 
 fn apply_seq<'a>(rules: &HashMap<u8, Rule>, seq: Sequence, input: &'a str) -> IResult<&'a str, ()> {
     seq.iter()
         .map(|rule| move |input| apply(rules, *rule, input))
-        .fold(Ok((input, ())), |last, func| {
-            last.and_then(|(rest, _)| func(rest))
-        })
+        .try_fold((input, ()), |(rest, _), func| func(rest))
 }
 
 fn apply<'a>(rules: &HashMap<u8, Rule>, root: u8, input: &'a str) -> IResult<&'a str, ()> {
@@ -103,8 +185,10 @@ fn apply_rule_42<'a>(
 ) -> IResult<&'a str, (usize, usize)> {
     let mut rest = input;
     let mut count = 0;
+    let mut ticker = Ticker::new(19, None);
 
     loop {
+        ticker.tick();
         let (input, _) = apply(rules, 42, rest)?;
         count += 1;
         rest = input;
@@ -119,7 +203,9 @@ fn apply_rule_42<'a>(
 fn apply_rule_31<'a>(rules: &HashMap<u8, Rule>, input: &'a str) -> IResult<&'a str, usize> {
     let mut rest = input;
     let mut count = 0;
+    let mut ticker = Ticker::new(19, None);
     loop {
+        ticker.tick();
         let res = apply(rules, 31, rest);
         if res.is_err() {
             return Ok((rest, count));
@@ -131,16 +217,17 @@ fn apply_rule_31<'a>(rules: &HashMap<u8, Rule>, input: &'a str) -> IResult<&'a s
 }
 
 #[aoc(day19, part1)]
-pub fn solve_p1((rules, tests): &(HashMap<u8, Rule>, Vec<String>)) -> usize {
+pub fn solve_p1((rules, tests): &(HashMap<u8, Rule>, Vec<String>)) -> Answer {
     tests
         .iter()
-        .filter(|line| apply(rules, 0, line.as_str()).map_or(false, |(i, _)| i.is_empty()))
+        .filter(|line| apply(rules, 0, line.as_str()).is_ok_and(|(i, _)| i.is_empty()))
         .count()
+        .into()
 }
 
 // Upping the Ante: From the rules, generate a HashMap<u8, Box<dyn FnMut(&str) -> IResult<&str, ()>>> (with any required pin/box/rc/etc) that can be used to parse each line just by calling `map[0](input)?`
 #[aoc(day19, part2)]
-pub fn solve_p2((rules, tests): &(HashMap<u8, Rule>, Vec<String>)) -> usize {
+pub fn solve_p2((rules, tests): &(HashMap<u8, Rule>, Vec<String>)) -> Answer {
     let mut all_rules = "8: 42 | 42 8\n11: 42 31 | 42 11 31"
         .lines()
         .map(|line| parse_rule(line).unwrap().1)
@@ -152,23 +239,53 @@ pub fn solve_p2((rules, tests): &(HashMap<u8, Rule>, Vec<String>)) -> usize {
 
     tests
         .iter()
-        .filter(|line| {
-            apply_rule_42(&all_rules, line.as_str()).map_or(false, |(i, _)| i.is_empty())
-        })
+        .filter(|line| apply_rule_42(&all_rules, line.as_str()).is_ok_and(|(i, _)| i.is_empty()))
         .count()
+        .into()
+}
+
+pub struct Day19;
+
+impl Puzzle for Day19 {
+    const DAY: u8 = 19;
+
+    type Input = (HashMap<u8, Rule>, Vec<String>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
+
+    fn check((rules, _): &Self::Input, part: u8) -> Result<(), Error> {
+        if part == 2 && !(rules.contains_key(&42) && rules.contains_key(&31)) {
+            return Err(Error::Unsolvable {
+                day: 19,
+                part: 2,
+                reason: "the looping rules 8 and 11 need rules 42 and 31",
+            });
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    fn p1_wrapper(input: &str) -> usize {
-        let parsed = input_generator(input);
+    fn p1_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p1(&parsed)
     }
 
-    fn p2_wrapper(input: &str) -> usize {
-        let parsed = input_generator(input);
+    fn p2_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p2(&parsed)
     }
 
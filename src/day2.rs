@@ -1,44 +1,47 @@
-use std::str::FromStr;
+use std::io::BufRead;
 
 use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{
     bytes::complete::tag,
-    bytes::complete::take_until,
-    character::complete::not_line_ending,
-    combinator::map,
-    multi::separated_list0,
+    character::complete::{anychar, digit1, not_line_ending},
+    combinator::{map, map_res, verify},
     sequence::{terminated, tuple},
+    IResult,
 };
 
-#[aoc_generator(day2)]
-pub fn input_generator(input: &str) -> Vec<(usize, usize, char, String)> {
-    let res: nom::IResult<_, _> = separated_list0(
-        tag("\n"),
-        tuple((
-            map(terminated(take_until("-"), tag("-")), |s: &str| {
-                s.parse::<usize>().unwrap()
-            }),
-            map(terminated(take_until(" "), tag(" ")), |s: &str| {
-                s.parse::<usize>().unwrap()
-            }),
-            map(terminated(take_until(": "), tag(": ")), |s: &str| {
-                FromStr::from_str(s).unwrap()
-            }),
-            map(not_line_ending, |s: &str| s.into()),
-        )),
-    )(input);
+use crate::answer::Answer;
+use crate::error::{Error, ParseError};
+use crate::puzzle::Puzzle;
+use crate::stream;
 
-    res.unwrap().1
+fn parse_position(input: &str) -> IResult<&str, usize> {
+    verify(map_res(digit1, |s: &str| s.parse::<usize>()), |pos| {
+        *pos > 0
+    })(input)
 }
 
-#[aoc(day2, part1)]
-pub fn solve_p1(input: &[(usize, usize, char, String)]) -> usize {
-    // let input: Vec<(usize, usize, char, String)> = vec![
-    //     (1, 3, 'a', "abcde".into()),
-    //     (1, 3, 'b', "cdefg".into()),
-    //     (2, 9, 'c', "ccccccccc".into()),
-    // ];
+fn parse_policy(input: &str) -> IResult<&str, (usize, usize, char, String)> {
+    tuple((
+        terminated(parse_position, tag("-")),
+        terminated(parse_position, tag(" ")),
+        terminated(anychar, tag(": ")),
+        map(not_line_ending, |s: &str| s.into()),
+    ))(input)
+}
+
+fn parse_line(line: &str) -> Result<(usize, usize, char, String), ParseError> {
+    parse_policy(line)
+        .map(|(_, policy)| policy)
+        .map_err(|e| ParseError::from_nom(line, e, "a policy like `1-3 a: abcde`"))
+}
 
+#[aoc_generator(day2)]
+pub fn input_generator(input: &str) -> Result<Vec<(usize, usize, char, String)>, ParseError> {
+    stream::lines(input, parse_line)
+}
+
+#[aoc(day2, part1)]
+pub fn solve_p1(input: &[(usize, usize, char, String)]) -> Answer {
     input
         .iter()
         .filter(|(l, h, c, s)| {
@@ -46,22 +49,42 @@ pub fn solve_p1(input: &[(usize, usize, char, String)]) -> usize {
             count >= *l && count <= *h
         })
         .count()
+        .into()
 }
 
 #[aoc(day2, part2)]
-pub fn solve_p2(input: &[(usize, usize, char, String)]) -> usize {
-    // let input: Vec<(usize, usize, char, String)> = vec![
-    //     (1, 3, 'a', "abcde".into()),
-    //     (1, 3, 'b', "cdefg".into()),
-    //     (2, 9, 'c', "ccccccccc".into()),
-    // ];
-
+pub fn solve_p2(input: &[(usize, usize, char, String)]) -> Answer {
     input
         .iter()
         .filter(|(l, h, c, s)| {
-            let first = s.chars().collect::<Vec<_>>()[l - 1] == *c;
-            let second = s.chars().collect::<Vec<_>>()[h - 1] == *c;
+            let first = s.chars().nth(l - 1) == Some(*c);
+            let second = s.chars().nth(h - 1) == Some(*c);
             first ^ second
         })
         .count()
+        .into()
+}
+
+pub struct Day2;
+
+impl Puzzle for Day2 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<(usize, usize, char, String)>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn read(reader: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        stream::read_lines(reader, parse_line)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
 }
@@ -1,34 +1,25 @@
 use std::{
     collections::{HashMap, HashSet},
+    convert::TryFrom,
     fmt::Display,
-    ops::Mul,
-    str::FromStr,
 };
 
 use aoc_runner_derive::{aoc, aoc_generator};
-use nalgebra::{
-    storage::Storage, ArrayStorage, Dim, DimName, Matrix, MatrixN, MatrixSlice, Scalar, U1, U10,
-    U24, U8, U96,
-};
-use nom::{
-    bytes::complete::{tag, take},
-    character::complete::digit1,
-    combinator::iterator,
-    IResult,
-};
+use nom::{bytes::complete::tag, character::complete::digit1, combinator::map_res, IResult};
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+use crate::answer::Answer;
+use crate::checked;
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::puzzle::Puzzle;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
 pub enum Pixel {
     On,
+    #[default]
     Off,
 }
 
-impl Default for Pixel {
-    fn default() -> Self {
-        Pixel::Off
-    }
-}
-
 impl Display for Pixel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -47,483 +38,152 @@ impl From<Pixel> for usize {
     }
 }
 
-impl FromStr for Pixel {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() > 1 {
-            Err(format!("'{}' is more than 1 character", s))
-        } else {
-            match s {
-                "#" => Ok(Pixel::On),
-                "." => Ok(Pixel::Off),
-                _ => Err(format!("'{}' is not a valid Pixel", s)),
-            }
+impl TryFrom<char> for Pixel {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '#' => Ok(Pixel::On),
+            '.' => Ok(Pixel::Off),
+            _ => Err(c),
         }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-enum Orientation {
-    Normal0,
-    Normal90,
-    Normal180,
-    Normal270,
-    Flipped0,
-    Flipped90,
-    Flipped180,
-    Flipped270,
-}
-
-trait Utilities<N: Scalar, D: Dim, S: Storage<N, D, D>> {
-    fn first_row(&self) -> MatrixSlice<N, U1, D, S::RStride, S::CStride>;
-    fn last_row(&self) -> MatrixSlice<N, U1, D, S::RStride, S::CStride>;
-
-    fn first_column(&self) -> MatrixSlice<N, D, U1, S::RStride, S::CStride>;
-    fn last_column(&self) -> MatrixSlice<N, D, U1, S::RStride, S::CStride>;
-
-    fn mirror_horizontal(&self) -> Self;
-    fn mirror_vertical(&self) -> Self;
-    fn rotate_clockwise(&self) -> Self;
-    fn rotate_counterclockwise(&self) -> Self;
+fn parse_header(input: &str) -> IResult<&str, usize> {
+    let (input, _) = tag("Tile ")(input)?;
+    let (input, id) = map_res(digit1, |s: &str| s.parse())(input)?;
+    let (input, _) = tag(":\n")(input)?;
 
-    fn to_orientation(&self, orient: Orientation) -> Self
-    where
-        Self: Sized;
+    Ok((input, id))
 }
 
-impl Utilities<Pixel, U10, ArrayStorage<Pixel, U10, U10>> for MatrixN<Pixel, U10> {
-    fn first_row(
-        &self,
-    ) -> MatrixSlice<
-        Pixel,
-        U1,
-        U10,
-        <ArrayStorage<Pixel, U10, U10> as Storage<Pixel, U10, U10>>::RStride,
-        <ArrayStorage<Pixel, U10, U10> as Storage<Pixel, U10, U10>>::CStride,
-    > {
-        self.row(0)
-    }
+fn parse_tile(input: &str, tile: &str) -> Result<(usize, Grid<Pixel>), ParseError> {
+    let (rest, id) = parse_header(tile)
+        .map_err(|e| ParseError::from_nom(input, e, "a header like `Tile 2311:`"))?;
 
-    fn last_row(
-        &self,
-    ) -> MatrixSlice<
-        Pixel,
-        U1,
-        U10,
-        <ArrayStorage<Pixel, U10, U10> as Storage<Pixel, U10, U10>>::RStride,
-        <ArrayStorage<Pixel, U10, U10> as Storage<Pixel, U10, U10>>::CStride,
-    > {
-        self.row(9)
+    if rest.lines().count() != 10 {
+        return Err(ParseError::at(input, rest, "10 rows of pixels"));
     }
-
-    fn first_column(
-        &self,
-    ) -> MatrixSlice<
-        Pixel,
-        U10,
-        U1,
-        <ArrayStorage<Pixel, U10, U10> as Storage<Pixel, U10, U10>>::RStride,
-        <ArrayStorage<Pixel, U10, U10> as Storage<Pixel, U10, U10>>::CStride,
-    > {
-        self.column(0)
+    if let Some(row) = rest.lines().find(|row| row.chars().count() != 10) {
+        return Err(ParseError::at(input, row, "a row of 10 pixels"));
     }
 
-    fn last_column(
-        &self,
-    ) -> MatrixSlice<
-        Pixel,
-        U10,
-        U1,
-        <ArrayStorage<Pixel, U10, U10> as Storage<Pixel, U10, U10>>::RStride,
-        <ArrayStorage<Pixel, U10, U10> as Storage<Pixel, U10, U10>>::CStride,
-    > {
-        self.column(9)
-    }
-
-    fn mirror_horizontal(&self) -> Self {
-        Matrix::<_, U10, U10, _>::from_fn(|i, j| self[(i, 9 - j)])
-    }
-
-    fn mirror_vertical(&self) -> Self {
-        Matrix::<_, U10, U10, _>::from_fn(|i, j| self[(9 - i, j)])
-    }
-
-    fn rotate_clockwise(&self) -> Self {
-        Matrix::<_, U10, U10, _>::from_fn(|i, j| self[(9 - j, i)])
-    }
+    let pixels = Grid::parse_in(input, rest, |c| Pixel::try_from(c).ok(), "`#` or `.`")?;
 
-    fn rotate_counterclockwise(&self) -> Self {
-        Matrix::<_, U10, U10, _>::from_fn(|i, j| self[(j, 9 - i)])
-    }
-
-    fn to_orientation(&self, orient: Orientation) -> Self
-    where
-        Self: Sized,
-    {
-        match orient {
-            Orientation::Normal0 => Matrix::<_, U10, U10, _>::from_fn(|i, j| self[(i, j)]),
-            Orientation::Normal90 => self.rotate_clockwise(),
-            Orientation::Normal180 => self.mirror_vertical(),
-            Orientation::Normal270 => self.rotate_counterclockwise(),
-            Orientation::Flipped0 => self.mirror_horizontal(),
-            Orientation::Flipped90 => self.mirror_horizontal().rotate_clockwise(),
-            Orientation::Flipped180 => self.mirror_horizontal().mirror_vertical(),
-            Orientation::Flipped270 => self.mirror_horizontal().rotate_counterclockwise(),
-        }
-    }
+    Ok((id, pixels))
 }
 
-impl Utilities<Pixel, U24, ArrayStorage<Pixel, U24, U24>> for MatrixN<Pixel, U24> {
-    fn first_row(
-        &self,
-    ) -> MatrixSlice<
-        Pixel,
-        U1,
-        U24,
-        <ArrayStorage<Pixel, U24, U24> as Storage<Pixel, U24, U24>>::RStride,
-        <ArrayStorage<Pixel, U24, U24> as Storage<Pixel, U24, U24>>::CStride,
-    > {
-        self.row(0)
-    }
-
-    fn last_row(
-        &self,
-    ) -> MatrixSlice<
-        Pixel,
-        U1,
-        U24,
-        <ArrayStorage<Pixel, U24, U24> as Storage<Pixel, U24, U24>>::RStride,
-        <ArrayStorage<Pixel, U24, U24> as Storage<Pixel, U24, U24>>::CStride,
-    > {
-        self.row(23)
-    }
-
-    fn first_column(
-        &self,
-    ) -> MatrixSlice<
-        Pixel,
-        U24,
-        U1,
-        <ArrayStorage<Pixel, U24, U24> as Storage<Pixel, U24, U24>>::RStride,
-        <ArrayStorage<Pixel, U24, U24> as Storage<Pixel, U24, U24>>::CStride,
-    > {
-        self.column(0)
-    }
-
-    fn last_column(
-        &self,
-    ) -> MatrixSlice<
-        Pixel,
-        U24,
-        U1,
-        <ArrayStorage<Pixel, U24, U24> as Storage<Pixel, U24, U24>>::RStride,
-        <ArrayStorage<Pixel, U24, U24> as Storage<Pixel, U24, U24>>::CStride,
-    > {
-        self.column(23)
-    }
-
-    fn mirror_horizontal(&self) -> Self {
-        Matrix::<_, U24, U24, _>::from_fn(|i, j| self[(i, 23 - j)])
-    }
-
-    fn mirror_vertical(&self) -> Self {
-        Matrix::<_, U24, U24, _>::from_fn(|i, j| self[(23 - i, j)])
-    }
-
-    fn rotate_clockwise(&self) -> Self {
-        Matrix::<_, U24, U24, _>::from_fn(|i, j| self[(23 - j, i)])
-    }
+#[aoc_generator(day20)]
+pub fn input_generator(input: &str) -> Result<HashMap<usize, Grid<Pixel>>, ParseError> {
+    let tiles = input
+        .split("\n\n")
+        .map(|tile| parse_tile(input, tile))
+        .collect::<Result<HashMap<_, _>, _>>()?;
 
-    fn rotate_counterclockwise(&self) -> Self {
-        Matrix::<_, U24, U24, _>::from_fn(|i, j| self[(j, 23 - i)])
+    if !(0..=tiles.len()).any(|side| side * side == tiles.len()) {
+        return Err(ParseError::end_of(input, "a square number of tiles"));
     }
 
-    fn to_orientation(&self, orient: Orientation) -> Self
-    where
-        Self: Sized,
-    {
-        match orient {
-            Orientation::Normal0 => Matrix::<_, U24, U24, _>::from_fn(|i, j| self[(i, j)]),
-            Orientation::Normal90 => self.rotate_clockwise(),
-            Orientation::Normal180 => self.mirror_vertical(),
-            Orientation::Normal270 => self.rotate_counterclockwise(),
-            Orientation::Flipped0 => self.mirror_horizontal(),
-            Orientation::Flipped90 => self.mirror_horizontal().rotate_clockwise(),
-            Orientation::Flipped180 => self.mirror_horizontal().mirror_vertical(),
-            Orientation::Flipped270 => self.mirror_horizontal().rotate_counterclockwise(),
-        }
-    }
+    Ok(tiles)
 }
 
-impl Utilities<Pixel, U96, ArrayStorage<Pixel, U96, U96>> for MatrixN<Pixel, U96> {
-    fn first_row(
-        &self,
-    ) -> MatrixSlice<
-        Pixel,
-        U1,
-        U96,
-        <ArrayStorage<Pixel, U96, U96> as Storage<Pixel, U96, U96>>::RStride,
-        <ArrayStorage<Pixel, U96, U96> as Storage<Pixel, U96, U96>>::CStride,
-    > {
-        self.row(0)
-    }
-
-    fn last_row(
-        &self,
-    ) -> MatrixSlice<
-        Pixel,
-        U1,
-        U96,
-        <ArrayStorage<Pixel, U96, U96> as Storage<Pixel, U96, U96>>::RStride,
-        <ArrayStorage<Pixel, U96, U96> as Storage<Pixel, U96, U96>>::CStride,
-    > {
-        self.row(95)
-    }
-
-    fn first_column(
-        &self,
-    ) -> MatrixSlice<
-        Pixel,
-        U96,
-        U1,
-        <ArrayStorage<Pixel, U96, U96> as Storage<Pixel, U96, U96>>::RStride,
-        <ArrayStorage<Pixel, U96, U96> as Storage<Pixel, U96, U96>>::CStride,
-    > {
-        self.column(0)
-    }
-
-    fn last_column(
-        &self,
-    ) -> MatrixSlice<
-        Pixel,
-        U96,
-        U1,
-        <ArrayStorage<Pixel, U96, U96> as Storage<Pixel, U96, U96>>::RStride,
-        <ArrayStorage<Pixel, U96, U96> as Storage<Pixel, U96, U96>>::CStride,
-    > {
-        self.column(95)
-    }
-
-    fn mirror_horizontal(&self) -> Self {
-        Matrix::<_, U96, U96, _>::from_fn(|i, j| self[(i, 95 - j)])
-    }
-
-    fn mirror_vertical(&self) -> Self {
-        Matrix::<_, U96, U96, _>::from_fn(|i, j| self[(95 - i, j)])
-    }
-
-    fn rotate_clockwise(&self) -> Self {
-        Matrix::<_, U96, U96, _>::from_fn(|i, j| self[(95 - j, i)])
-    }
-
-    fn rotate_counterclockwise(&self) -> Self {
-        Matrix::<_, U96, U96, _>::from_fn(|i, j| self[(j, 95 - i)])
-    }
-
-    fn to_orientation(&self, orient: Orientation) -> Self
-    where
-        Self: Sized,
-    {
-        match orient {
-            Orientation::Normal0 => Matrix::<_, U96, U96, _>::from_fn(|i, j| self[(i, j)]),
-            Orientation::Normal90 => self.rotate_clockwise(),
-            Orientation::Normal180 => self.mirror_vertical(),
-            Orientation::Normal270 => self.rotate_counterclockwise(),
-            Orientation::Flipped0 => self.mirror_horizontal(),
-            Orientation::Flipped90 => self.mirror_horizontal().rotate_clockwise(),
-            Orientation::Flipped180 => self.mirror_horizontal().mirror_vertical(),
-            Orientation::Flipped270 => self.mirror_horizontal().rotate_counterclockwise(),
-        }
-    }
+fn as_number<'a>(edge: impl Iterator<Item = &'a Pixel>) -> usize {
+    edge.fold(0, |acc, pixel| acc << 1 | usize::from(*pixel))
 }
 
-fn parse_tile(input: &str) -> IResult<&str, (usize, MatrixN<Pixel, U10>)> {
-    let (input, _) = tag("Tile ")(input)?;
-    let (input, id) = digit1(input)?;
-    let (input, _) = tag(":\n")(input)?;
-    let mut pixels_iter = iterator(input, take(1usize));
-    let pixels: Vec<Pixel> = pixels_iter.filter_map(|c: &str| c.parse().ok()).collect();
-
-    let pixels_matrix = Matrix::<_, U10, U10, _>::from_row_slice(&pixels);
-    let res = pixels_iter.finish();
+fn first_row(tile: &Grid<Pixel>) -> usize {
+    as_number(tile.row(0).iter())
+}
 
-    res.map(|(input, _)| (input, (id.parse().unwrap(), pixels_matrix)))
+fn last_row(tile: &Grid<Pixel>) -> usize {
+    as_number(tile.row(tile.height() - 1).iter())
 }
 
-#[aoc_generator(day20)]
-pub fn input_generator(input: &str) -> HashMap<usize, MatrixN<Pixel, U10>> {
-    input
-        .split("\n\n")
-        .map(|tile| parse_tile(tile).unwrap().1)
-        .collect()
+fn first_column(tile: &Grid<Pixel>) -> usize {
+    as_number(tile.column(0))
 }
 
-fn as_number<R: Dim, C: Dim, RStride: Dim, CStride: Dim>(
-    tile: &MatrixSlice<Pixel, R, C, RStride, CStride>,
-) -> usize {
-    tile.iter()
-        .fold(0, |acc, pixel| acc << 1 | usize::from(*pixel))
+fn last_column(tile: &Grid<Pixel>) -> usize {
+    as_number(tile.column(tile.width() - 1))
 }
 
-#[aoc(day20, part1)]
-pub fn solve_p1(tiles: &HashMap<usize, MatrixN<Pixel, U10>>) -> usize {
-    let connections = tiles.iter().fold(
+/// Every edge, read in either direction, mapped to the tiles that have it.
+fn edges(tiles: &HashMap<usize, Grid<Pixel>>) -> HashMap<usize, HashSet<usize>> {
+    tiles.iter().fold(
         HashMap::<usize, HashSet<usize>>::new(),
         |mut map, (id, tile)| {
-            vec![
-                Orientation::Normal0,
-                Orientation::Normal90,
-                Orientation::Normal180,
-                Orientation::Normal270,
-                Orientation::Flipped0,
-                Orientation::Flipped90,
-                Orientation::Flipped180,
-                Orientation::Flipped270,
-            ]
-            .iter()
-            .for_each(|orient| {
-                let tile = tile.to_orientation(*orient);
-                map.entry(as_number(&tile.first_row()))
+            tile.orientations().iter().for_each(|tile| {
+                map.entry(first_row(tile))
                     .or_insert_with(HashSet::new)
                     .insert(*id);
             });
             map
         },
-    );
+    )
+}
 
-    tiles
+fn is_corner(id: &usize, connections: &HashMap<usize, HashSet<usize>>) -> bool {
+    connections
         .iter()
-        .filter(|(id, _)| {
-            connections
-                .iter()
-                .filter(|(_, set)| set.contains(id) && set.len() == 2)
-                .count()
-                == 4
-        })
-        .map(|(id, _)| id)
-        .product()
+        .filter(|(_, set)| set.contains(id) && set.len() == 2)
+        .count()
+        == 4
 }
 
-fn connections(tiles: &HashMap<usize, MatrixN<Pixel, U10>>) -> HashMap<usize, HashSet<usize>> {
-    tiles
-        .iter()
-        .fold(
-            HashMap::<usize, HashSet<usize>>::new(),
-            |mut map, (id, tile)| {
-                vec![
-                    Orientation::Normal0,
-                    Orientation::Normal90,
-                    Orientation::Normal180,
-                    Orientation::Normal270,
-                    Orientation::Flipped0,
-                    Orientation::Flipped90,
-                    Orientation::Flipped180,
-                    Orientation::Flipped270,
-                ]
-                .iter()
-                .for_each(|orient| {
-                    let tile = tile.to_orientation(*orient);
-                    map.entry(as_number(&tile.first_row()))
-                        .or_insert_with(HashSet::new)
-                        .insert(*id);
-                });
-                map
-            },
-        )
-        .iter()
+#[aoc(day20, part1)]
+pub fn solve_p1(tiles: &HashMap<usize, Grid<Pixel>>) -> Answer {
+    let connections = edges(tiles);
+
+    let corners = tiles
+        .keys()
+        .copied()
+        .filter(|id| is_corner(id, &connections));
+    checked::product(20, "corner product", corners).into()
+}
+
+fn connections(tiles: &HashMap<usize, Grid<Pixel>>) -> HashMap<usize, HashSet<usize>> {
+    edges(tiles)
+        .into_iter()
         .filter(|(_, tiles)| tiles.len() == 2)
-        .map(|(ind, tiles)| (*ind, tiles.clone()))
         .collect()
 }
 
 fn orient(
-    tile: &MatrixN<Pixel, U10>,
+    tile: &Grid<Pixel>,
     north: Option<usize>,
     west: Option<usize>,
     connections: &HashMap<usize, HashSet<usize>>,
-) -> MatrixN<Pixel, U10> {
-    vec![
-        Orientation::Normal0,
-        Orientation::Normal90,
-        Orientation::Normal180,
-        Orientation::Normal270,
-        Orientation::Flipped0,
-        Orientation::Flipped90,
-        Orientation::Flipped180,
-        Orientation::Flipped270,
-    ]
-    .iter()
-    .filter_map(|orient| {
-        let tile = tile.to_orientation(*orient);
-        let n_check = as_number(&tile.first_row());
-        let w_check = as_number(&tile.first_column());
-
-        if ((north.is_none() && !connections.contains_key(&n_check)) || north == Some(n_check))
-            && ((west.is_none() && !connections.contains_key(&w_check)) || west == Some(w_check))
-        {
-            Some(tile)
-        } else {
-            None
-        }
-    })
-    .next()
-    .unwrap()
-}
-
-fn get_north((row, col): (usize, usize), tiles: &[(usize, MatrixN<Pixel, U10>)]) -> Option<usize> {
-    if row > 0 {
-        let rowlen = (tiles.len() - col) / row;
-        let ind = row * rowlen + col - rowlen;
-        // println!("\t{}/{} -> {}", row, col, ind);
-
-        let (_, tile) = tiles[ind];
-
-        Some(as_number(&tile.last_row()))
-    } else {
-        None
-    }
-}
-
-fn get_west((row, col): (usize, usize), tiles: &[(usize, MatrixN<Pixel, U10>)]) -> Option<usize> {
-    if col > 0 {
-        let ind = if row > 0 {
-            let rowlen = (tiles.len() - col) / row;
-            row * rowlen + col - 1
-        } else {
-            col - 1
-        };
-
-        // println!("\t{}/{} -> {}", row, col, ind);
-        let (_, tile) = tiles[ind];
-
-        Some(as_number(&tile.last_column()))
-    } else {
-        None
-    }
+) -> Grid<Pixel> {
+    tile.orientations()
+        .into_iter()
+        .find(|tile| {
+            let n_check = first_row(tile);
+            let w_check = first_column(tile);
+
+            ((north.is_none() && !connections.contains_key(&n_check)) || north == Some(n_check))
+                && ((west.is_none() && !connections.contains_key(&w_check))
+                    || west == Some(w_check))
+        })
+        .unwrap()
 }
 
-fn stitch<D: Dim + DimName>(tiles: &HashMap<usize, MatrixN<Pixel, U10>>) -> MatrixN<Pixel, D>
-where
-    <D as DimName>::Value: Mul,
-    <<D as DimName>::Value as Mul>::Output: generic_array::ArrayLength<Pixel>,
-    <<D as DimName>::Value as Mul>::Output: generic_array::ArrayLength<usize>,
-{
+fn stitch(tiles: &HashMap<usize, Grid<Pixel>>) -> Grid<Pixel> {
     let mut used = HashSet::new();
     let connections = connections(tiles);
-    let mut target = Matrix::<_, D, D, _>::from_fn(|_, _| Pixel::Off);
-
-    let mut ids = Vec::new();
+    let side = (0..).find(|x| x * x >= tiles.len()).unwrap();
+    let mut target = Grid::new(side * 8, side * 8, Pixel::Off);
 
-    // println!("{:?}", connections);
+    let mut placed: Vec<Grid<Pixel>> = Vec::with_capacity(tiles.len());
 
-    let count = tiles.len();
-    for col in (0..).take_while(|x| x * x < count) {
-        for row in (0..).take_while(|x| x * x < count) {
-            // everything else
-            let north = get_north((col, row), &ids);
-            let west = get_west((col, row), &ids);
-            // println!("{}/{} -> {:?} {:?}", col, row, north, west);
+    for row in 0..side {
+        for col in 0..side {
+            let north = row
+                .checked_sub(1)
+                .map(|above| last_row(&placed[above * side + col]));
+            let west = col
+                .checked_sub(1)
+                .map(|left| last_column(&placed[row * side + left]));
 
             let (id, tile) = if let Some(connection) = north.or(west) {
                 let id = connections[&connection]
@@ -531,43 +191,29 @@ where
                     .find(|e| !used.contains(*e))
                     .unwrap();
 
-                let tile = orient(&tiles[&id], north, west, &connections);
-
-                (id, tile)
+                (id, orient(&tiles[id], north, west, &connections))
             } else {
                 // first tile
                 tiles
                     .iter()
-                    .filter(|(id, _)| {
-                        connections
-                            .iter()
-                            .filter(|(_, set)| set.contains(id) && set.len() == 2)
-                            .count()
-                            == 4
-                    })
+                    .find(|(id, _)| is_corner(id, &connections))
                     .map(|(id, tile)| (id, orient(tile, None, None, &connections)))
-                    .next()
                     .unwrap()
             };
 
-            // println!("\t{}", id);
+            trace!(20, "place", row, col, tile = id);
 
-            ids.push((*id, tile));
-
-            let tile = tile.fixed_slice::<U8, U8>(1, 1);
-
-            target
-                .fixed_slice_mut::<U8, U8>(col * 8, row * 8)
-                .iter_mut()
-                .enumerate()
-                .for_each(|(ind, val)| *val = tile[ind]);
+            for i in 0..8 {
+                for j in 0..8 {
+                    target[(row * 8 + i, col * 8 + j)] = tile[(i + 1, j + 1)];
+                }
+            }
 
+            placed.push(tile);
             used.insert(*id);
         }
     }
 
-    // ids.iter().for_each(|(id, tile)| println!("{}:{}", id, tile));
-
     target
 }
 
@@ -577,106 +223,87 @@ where
 #....##....##....###
 .#..#..#..#..#..#...
  */
-
-// TODO: This is the last required step to fix my solution.
-// Then replace the necessary U24s to U96s and once it compiles we got it
-fn count_serpents<D: DimName>(picture: &MatrixN<Pixel, D>) -> usize
-where
-    <D as DimName>::Value: Mul,
-    <<D as DimName>::Value as Mul>::Output: generic_array::ArrayLength<Pixel>,
-    <<D as DimName>::Value as Mul>::Output: generic_array::ArrayLength<usize>,
-{
-    let (rows, cols) = picture.shape();
-
-    let mut count = 0;
-
-    for j in 0..rows - 2 {
-        for i in 0..cols - 19 {
-            // println!("{} {} {} {}", i, j, cols, rows);
-
-            if picture.get((i + 18, j)) == Some(&Pixel::On)
-                && picture.get((i, j + 1)) == Some(&Pixel::On)
-                && picture.get((i + 1, j + 2)) == Some(&Pixel::On)
-                && picture.get((i + 4, j + 2)) == Some(&Pixel::On)
-                && picture.get((i + 5, j + 1)) == Some(&Pixel::On)
-                && picture.get((i + 6, j + 1)) == Some(&Pixel::On)
-                && picture.get((i + 7, j + 2)) == Some(&Pixel::On)
-                && picture.get((i + 10, j + 2)) == Some(&Pixel::On)
-                && picture.get((i + 11, j + 1)) == Some(&Pixel::On)
-                && picture.get((i + 12, j + 1)) == Some(&Pixel::On)
-                && picture.get((i + 13, j + 2)) == Some(&Pixel::On)
-                && picture.get((i + 16, j + 2)) == Some(&Pixel::On)
-                && picture.get((i + 17, j + 1)) == Some(&Pixel::On)
-                && picture.get((i + 18, j + 1)) == Some(&Pixel::On)
-                && picture.get((i + 19, j + 1)) == Some(&Pixel::On)
-            {
-                // println!("\tFOUND");
-                count += 1;
-            }
-        }
-    }
-
-    count
+const SERPENT: [(usize, usize); 15] = [
+    (0, 18),
+    (1, 0),
+    (1, 5),
+    (1, 6),
+    (1, 11),
+    (1, 12),
+    (1, 17),
+    (1, 18),
+    (1, 19),
+    (2, 1),
+    (2, 4),
+    (2, 7),
+    (2, 10),
+    (2, 13),
+    (2, 16),
+];
+
+fn count_serpents(picture: &Grid<Pixel>) -> usize {
+    picture
+        .positions()
+        .filter(|&(row, col)| {
+            SERPENT
+                .iter()
+                .all(|&(i, j)| picture.get((row + i, col + j)) == Some(&Pixel::On))
+        })
+        .count()
 }
 
-fn serpent_count(picture: &MatrixN<Pixel, U96>) -> usize
-//where
-//    <D as DimName>::Value: Mul,
-//    <<D as DimName>::Value as Mul>::Output: generic_array::ArrayLength<Pixel>,
-//    <<D as DimName>::Value as Mul>::Output: generic_array::ArrayLength<usize>,
-{
-    vec![
-        Orientation::Normal0,
-        Orientation::Normal90,
-        Orientation::Normal180,
-        Orientation::Normal270,
-        Orientation::Flipped0,
-        Orientation::Flipped90,
-        Orientation::Flipped180,
-        Orientation::Flipped270,
-    ]
-    .iter()
-    .filter_map(|orient| {
-        let picture = picture.to_orientation(*orient);
-
-        let count = count_serpents(&picture);
-
-        // println!("{:?} -> {}:{}", orient, count, picture);
-
-        if count > 0 {
-            Some(count)
-        } else {
-            None
-        }
-    })
-    .max()
-    .unwrap()
+fn serpent_count(picture: &Grid<Pixel>) -> usize {
+    picture
+        .orientations()
+        .iter()
+        .map(count_serpents)
+        .max()
+        .unwrap_or(0)
 }
 
 #[aoc(day20, part2)]
-pub fn solve_p2(tiles: &HashMap<usize, MatrixN<Pixel, U10>>) -> usize {
-    let picture = stitch::<U96>(tiles);
+pub fn solve_p2(tiles: &HashMap<usize, Grid<Pixel>>) -> Answer {
+    let picture = stitch(tiles);
 
-    // println!("{}", picture);
-
-    picture
+    (picture
         .iter()
         .map(|pixel| usize::from(*pixel))
         .sum::<usize>()
-        - serpent_count(&picture) * 15
+        - serpent_count(&picture) * SERPENT.len())
+    .into()
+}
+
+pub struct Day20;
+
+impl Puzzle for Day20 {
+    const DAY: u8 = 20;
+
+    type Input = HashMap<usize, Grid<Pixel>>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    fn p1_wrapper(input: &str) -> usize {
-        let parsed = input_generator(input);
+    fn p1_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p1(&parsed)
     }
 
-    fn p2_wrapper(input: &str) -> usize {
-        let parsed = input_generator(input);
+    fn p2_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p2(&parsed)
     }
 
@@ -689,4 +316,9 @@ mod test {
     fn test_p2() {
         assert_eq!(p2_wrapper("Tile 2311:\n..##.#..#.\n##..#.....\n#...##..#.\n####.#...#\n##.##.###.\n##...#.###\n.#.#.#..##\n..#....#..\n###...#.#.\n..###..###\n\nTile 1951:\n#.##...##.\n#.####...#\n.....#..##\n#...######\n.##.#....#\n.###.#####\n###.##.##.\n.###....#.\n..#.#..#.#\n#...##.#..\n\nTile 1171:\n####...##.\n#..##.#..#\n##.#..#.#.\n.###.####.\n..###.####\n.##....##.\n.#...####.\n#.##.####.\n####..#...\n.....##...\n\nTile 1427:\n###.##.#..\n.#..#.##..\n.#.##.#..#\n#.#.#.##.#\n....#...##\n...##..##.\n...#.#####\n.#.####.#.\n..#..###.#\n..##.#..#.\n\nTile 1489:\n##.#.#....\n..##...#..\n.##..##...\n..#...#...\n#####...#.\n#..#.#.#.#\n...#.#.#..\n##.#...##.\n..##.##.##\n###.##.#..\n\nTile 2473:\n#....####.\n#..#.##...\n#.##..#...\n######.#.#\n.#...#.#.#\n.#########\n.###.#..#.\n########.#\n##...##.#.\n..###.#.#.\n\nTile 2971:\n..#.#....#\n#...###...\n#.#.###...\n##.##..#..\n.#####..##\n.#..####.#\n#..#.#..#.\n..####.###\n..#.#.###.\n...#.#.#.#\n\nTile 2729:\n...#.#.#.#\n####.#....\n..#.#.....\n....#..#.#\n.##..##.#.\n.#.####...\n####.#.#..\n##.####...\n##..#.##..\n#.##...##.\n\nTile 3079:\n#.#.#####.\n.#..######\n..#.......\n######....\n####.#..#.\n.#...#.##.\n#.#####.##\n..#.###...\n..#.......\n..#.###..."), 273);
     }
+
+    #[test]
+    fn test_no_serpents() {
+        assert_eq!(serpent_count(&Grid::new(24, 24, Pixel::Off)), 0);
+    }
 }
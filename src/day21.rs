@@ -1,26 +1,37 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use aoc_runner_derive::{aoc, aoc_generator};
+use itertools::Itertools;
+
+use crate::answer::Answer;
+use crate::assignment;
+use crate::error::ParseError;
+use crate::explain::Step;
+use crate::puzzle::Puzzle;
+
+type Food = (Vec<String>, Vec<String>);
 
 #[aoc_generator(day21)]
-pub fn input_generator(input: &str) -> Vec<(Vec<String>, Vec<String>)> {
+pub fn input_generator(input: &str) -> Result<Vec<Food>, ParseError> {
     input
         .lines()
         .map(|line| {
-            let (left, right) = line.split_once(" (contains ").unwrap();
-            (
+            let (left, right) = line
+                .split_once(" (contains ")
+                .and_then(|(left, right)| Some((left, right.strip_suffix(')')?)))
+                .ok_or_else(|| {
+                    ParseError::at(input, line, "a food like `abc def (contains dairy, fish)`")
+                })?;
+            Ok((
                 left.split(' ').map(|i| i.to_owned()).collect(),
-                right[..right.len() - 1]
-                    .split(", ")
-                    .map(|i| i.to_owned())
-                    .collect(),
-            )
+                right.split(", ").map(|i| i.to_owned()).collect(),
+            ))
         })
         .collect()
 }
 
-#[aoc(day21, part1)]
-pub fn solve_p1(rules: &[(Vec<String>, Vec<String>)]) -> usize {
+/// The ingredients each allergen could be in: those in every food listing it.
+fn candidates(rules: &[Food]) -> HashMap<&String, HashSet<String>> {
     let all_ingredients = rules
         .iter()
         .flat_map(|i| i.0.clone())
@@ -41,15 +52,42 @@ pub fn solve_p1(rules: &[(Vec<String>, Vec<String>)]) -> usize {
         }
     }
 
-    // println!("{:?}", allergies);
+    trace!(21, "candidates", allergies);
+
+    allergies
+}
+
+fn safe_ingredients(
+    rules: &[Food],
+    allergies: &HashMap<&String, HashSet<String>>,
+) -> HashSet<String> {
+    let all_ingredients = rules
+        .iter()
+        .flat_map(|i| i.0.clone())
+        .collect::<HashSet<_>>();
 
-    let good_ingredients = allergies
+    allergies
         .iter()
         .fold(all_ingredients, |acc, (_, ingredients)| {
             acc.difference(ingredients).cloned().collect()
-        });
+        })
+}
+
+/// The one ingredient each allergen can be in once the others have taken theirs, by allergen.
+fn resolve<'a>(allergies: &HashMap<&'a String, HashSet<String>>) -> BTreeMap<&'a String, String> {
+    let resolved = assignment::solve(allergies)
+        .unwrap_or_else(|err| panic!("allergens can't be matched to ingredients: {}", err));
+
+    trace!(21, "resolved", allergies = resolved);
 
-    // println!("{:?}", good_ingredients);
+    resolved
+}
+
+#[aoc(day21, part1)]
+pub fn solve_p1(rules: &[Food]) -> Answer {
+    let good_ingredients = safe_ingredients(rules, &candidates(rules));
+
+    trace!(21, "safe", ingredients = good_ingredients);
 
     rules
         .iter()
@@ -59,72 +97,96 @@ pub fn solve_p1(rules: &[(Vec<String>, Vec<String>)]) -> usize {
                 .filter(|ingredient| good_ingredients.contains(*ingredient))
         })
         .count()
+        .into()
 }
 
 #[aoc(day21, part2)]
-pub fn solve_p2(rules: &[(Vec<String>, Vec<String>)]) -> String {
-    let all_ingredients = rules
+pub fn solve_p2(rules: &[Food]) -> Answer {
+    resolve(&candidates(rules))
+        .into_values()
+        .collect::<Vec<_>>()
+        .join(",")
+        .into()
+}
+
+fn candidates_step(allergies: &HashMap<&String, HashSet<String>>) -> Step {
+    allergies
         .iter()
-        .flat_map(|i| i.0.clone())
-        .collect::<HashSet<_>>();
+        .sorted_by_key(|(allergen, _)| **allergen)
+        .fold(
+            Step::new("ingredients in every food listing each allergen"),
+            |step, (allergen, ingredients)| {
+                step.fact(allergen, ingredients.iter().sorted().join(", "))
+            },
+        )
+}
 
-    let mut allergies = HashMap::new();
+fn explain_p1(rules: &[Food]) -> Vec<Step> {
+    let allergies = candidates(rules);
+    let safe = safe_ingredients(rules, &allergies);
+    let appearances = safe.iter().sorted().fold(
+        Step::new("ingredients that can't contain any of them"),
+        |step, ingredient| {
+            let count = rules
+                .iter()
+                .filter(|(ingredients, _)| ingredients.contains(ingredient))
+                .count();
+            step.fact(ingredient, format!("in {} of {} foods", count, rules.len()))
+        },
+    );
 
-    for (ingredients, allergens) in rules.iter() {
-        let ingredients = ingredients.iter().cloned().collect::<HashSet<_>>();
-        for allergen in allergens {
-            let diff = allergies
-                .entry(allergen)
-                .or_insert_with(|| all_ingredients.clone())
-                .intersection(&ingredients)
-                .cloned()
-                .collect();
-            allergies.entry(allergen).and_modify(|value| *value = diff);
-        }
+    vec![candidates_step(&allergies), appearances]
+}
+
+fn explain_p2(rules: &[Food]) -> Vec<Step> {
+    let allergies = candidates(rules);
+    let resolved = resolve(&allergies).into_iter().fold(
+        Step::new("each allergen's ingredient, once the others have taken theirs"),
+        |step, (allergen, ingredient)| step.fact(allergen, ingredient),
+    );
+
+    vec![candidates_step(&allergies), resolved]
+}
+
+pub struct Day21;
+
+impl Puzzle for Day21 {
+    const DAY: u8 = 21;
+
+    type Input = Vec<Food>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
     }
 
-    while allergies
-        .iter()
-        .filter(|(_, candidates)| candidates.len() > 1)
-        .count()
-        > 0
-    {
-        allergies = allergies
-            .iter()
-            .map(|(k, ingredients)| {
-                let ingredients = allergies
-                    .iter()
-                    .filter(|(bad, ingredients)| ingredients.len() == 1 && *bad != k)
-                    .fold(ingredients.clone(), |acc, (_, ingredients)| {
-                        acc.difference(ingredients).cloned().collect()
-                    });
-                (*k, ingredients)
-            })
-            .collect();
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
     }
 
-    // println!("{:?}", allergies);
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
 
-    let mut bad_stuff = allergies.iter().collect::<Vec<_>>();
-    bad_stuff.sort_by_cached_key(|(k, _)| **k);
-    bad_stuff
-        .iter()
-        .map(|(_, v)| v.iter().cloned().next().unwrap())
-        .collect::<Vec<_>>()
-        .join(",")
+    fn explain(input: &Self::Input, part: u8) -> Option<Vec<Step>> {
+        match part {
+            1 => Some(explain_p1(input)),
+            2 => Some(explain_p2(input)),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    fn p1_wrapper(input: &str) -> usize {
-        let parsed = input_generator(input);
+    fn p1_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p1(&parsed)
     }
 
-    fn p2_wrapper(input: &str) -> String {
-        let parsed = input_generator(input);
+    fn p2_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p2(&parsed)
     }
 
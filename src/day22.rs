@@ -5,32 +5,47 @@ use std::{
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
-#[aoc_generator(day22)]
-pub fn input_generator(input: &str) -> (VecDeque<u8>, VecDeque<u8>) {
-    let mut input = input.split("\n\n");
-
-    let first = input
-        .next()
-        .map(|player| {
-            player
-                .lines()
-                .skip(1)
-                .map(|e| e.parse::<u8>().unwrap())
-                .collect::<VecDeque<_>>()
-        })
-        .unwrap();
-    let second = input
-        .next()
-        .map(|player| {
-            player
-                .lines()
-                .skip(1)
-                .map(|e| e.parse::<u8>().unwrap())
-                .collect::<VecDeque<_>>()
+use crate::answer::Answer;
+use crate::checked;
+use crate::context::Ticker;
+use crate::error::{parse_at, ParseError};
+use crate::puzzle::Puzzle;
+
+/// Parses one player's deck, adding its cards to `dealt`. Every card is different, or a
+/// round could end in a tie.
+fn parse_deck(
+    input: &str,
+    player: Option<&str>,
+    name: &str,
+    dealt: &mut HashSet<u8>,
+) -> Result<VecDeque<u8>, ParseError> {
+    let player = player.ok_or_else(|| ParseError::end_of(input, format!("`{}`", name)))?;
+    let mut lines = player.lines();
+    if lines.next() != Some(name) {
+        return Err(ParseError::at(input, player, format!("`{}`", name)));
+    }
+
+    lines
+        .map(|e| match parse_at(input, e, "a card value")? {
+            card if dealt.insert(card) => Ok(card),
+            _ => Err(ParseError::at(
+                input,
+                e,
+                "a card that hasn't been dealt already",
+            )),
         })
-        .unwrap();
+        .collect()
+}
 
-    (first, second)
+#[aoc_generator(day22)]
+pub fn input_generator(input: &str) -> Result<(VecDeque<u8>, VecDeque<u8>), ParseError> {
+    let mut input_iter = input.split("\n\n");
+    let mut dealt = HashSet::new();
+
+    let first = parse_deck(input, input_iter.next(), "Player 1:", &mut dealt)?;
+    let second = parse_deck(input, input_iter.next(), "Player 2:", &mut dealt)?;
+
+    Ok((first, second))
 }
 
 enum RoundWinner {
@@ -71,25 +86,26 @@ fn iter(p1: &mut VecDeque<u8>, p2: &mut VecDeque<u8>) -> RoundWinner {
     }
 }
 
+/// Each card times its position counted from the bottom of the deck.
+fn score(deck: &VecDeque<u8>) -> usize {
+    checked::sum(
+        22,
+        "score",
+        deck.iter()
+            .rev()
+            .enumerate()
+            .map(|(i, &card)| checked::mul(22, "score", i + 1, card as usize)),
+    )
+}
+
 fn play(p1: &mut VecDeque<u8>, p2: &mut VecDeque<u8>) -> usize {
+    // nothing stops a game of plain Combat that comes back around to an earlier round
+    let mut ticker = Ticker::new(22, None);
     loop {
+        ticker.tick();
         match check_winner(p1, p2) {
-            GameWinner::P1 => {
-                return p1
-                    .iter()
-                    .rev()
-                    .enumerate()
-                    .map(|(i, j)| (i + 1) * *j as usize)
-                    .sum()
-            }
-            GameWinner::P2 => {
-                return p2
-                    .iter()
-                    .rev()
-                    .enumerate()
-                    .map(|(i, j)| (i + 1) * *j as usize)
-                    .sum()
-            }
+            GameWinner::P1 => return score(p1),
+            GameWinner::P2 => return score(p2),
             _ => {}
         }
 
@@ -107,35 +123,34 @@ fn play(p1: &mut VecDeque<u8>, p2: &mut VecDeque<u8>) -> usize {
 }
 
 #[aoc(day22, part1)]
-pub fn solve_p1((p1, p2): &(VecDeque<u8>, VecDeque<u8>)) -> usize {
+pub fn solve_p1((p1, p2): &(VecDeque<u8>, VecDeque<u8>)) -> Answer {
     let mut p1 = p1.clone();
     let mut p2 = p2.clone();
 
-    play(&mut p1, &mut p2)
+    play(&mut p1, &mut p2).into()
 }
 
 fn iter_recursive(p1: &mut VecDeque<u8>, p2: &mut VecDeque<u8>) -> RoundWinner {
     let first = p1.pop_front();
     let second = p2.pop_front();
 
-    // println!("Player 1 plays: {:?}", first);
-    // println!("Player 2 plays: {:?}", second);
+    trace!(22, "play", p1 = first, p2 = second);
 
     match first {
         Some(first) => match second {
             Some(second) => {
-                if first <= p1.len() as u8 && second <= p2.len() as u8 {
+                if first as usize <= p1.len() && second as usize <= p2.len() {
                     let mut subgame_p1 = p1.iter().copied().take(first as usize).collect();
                     let mut subgame_p2 = p2.iter().copied().take(second as usize).collect();
 
-                    // println!("Playing a subgame to determine the winner");
+                    trace!(22, "subgame", p1 = subgame_p1, p2 = subgame_p2);
                     match play_recursive(&mut subgame_p1, &mut subgame_p2) {
                         GameWinner::P1 => {
-                            // println!("Player 1 won the subgame");
+                            trace!(22, "subgame won", winner = 1);
                             RoundWinner::P1(first, second)
                         }
                         GameWinner::P2 => {
-                            // println!("Player 2 won the subgame");
+                            trace!(22, "subgame won", winner = 2);
                             RoundWinner::P2(first, second)
                         }
                         _ => unreachable!(),
@@ -177,18 +192,16 @@ fn play_recursive(p1: &mut VecDeque<u8>, p2: &mut VecDeque<u8>) -> GameWinner {
             a => return a,
         }
 
-        // println!("Next Round:");
-        // println!("Player 1: {:?}", p1);
-        // println!("Player 2: {:?}", p2);
+        trace!(22, "round", p1, p2);
 
         match iter_recursive(p1, p2) {
             RoundWinner::P1(first, second) => {
-                // println!("Player 1 wins this round");
+                trace!(22, "round won", winner = 1);
                 p1.push_back(first);
                 p1.push_back(second);
             }
             RoundWinner::P2(first, second) => {
-                // println!("Player 2 wins this round");
+                trace!(22, "round won", winner = 2);
                 p2.push_back(second);
                 p2.push_back(first);
             }
@@ -197,42 +210,48 @@ fn play_recursive(p1: &mut VecDeque<u8>, p2: &mut VecDeque<u8>) -> GameWinner {
 }
 
 #[aoc(day22, part2)]
-pub fn solve_p2((p1, p2): &(VecDeque<u8>, VecDeque<u8>)) -> usize {
+pub fn solve_p2((p1, p2): &(VecDeque<u8>, VecDeque<u8>)) -> Answer {
     let mut p1 = p1.clone();
     let mut p2 = p2.clone();
 
     match play_recursive(&mut p1, &mut p2) {
-        GameWinner::P1 => {
-            return p1
-                .iter()
-                .rev()
-                .enumerate()
-                .map(|(i, j)| (i + 1) * *j as usize)
-                .sum()
-        }
-        GameWinner::P2 => {
-            return p2
-                .iter()
-                .rev()
-                .enumerate()
-                .map(|(i, j)| (i + 1) * *j as usize)
-                .sum()
-        }
+        GameWinner::P1 => score(&p1).into(),
+        GameWinner::P2 => score(&p2).into(),
         _ => unreachable!(),
     }
 }
 
+pub struct Day22;
+
+impl Puzzle for Day22 {
+    const DAY: u8 = 22;
+
+    type Input = (VecDeque<u8>, VecDeque<u8>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
-    fn p1_wrapper(input: &str) -> usize {
-        let parsed = input_generator(input);
+    fn p1_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p1(&parsed)
     }
 
-    fn p2_wrapper(input: &str) -> usize {
-        let parsed = input_generator(input);
+    fn p2_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p2(&parsed)
     }
 
@@ -1,8 +1,34 @@
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::answer::Answer;
+use crate::bigint::Exact;
+use crate::context::Ticker;
+use crate::error::ParseError;
+use crate::puzzle::Puzzle;
+
 #[aoc_generator(day23)]
-pub fn input_generator(input: &str) -> Vec<usize> {
-    input.as_bytes().iter().map(|e| (e - 48) as usize).collect()
+pub fn input_generator(input: &str) -> Result<Vec<usize>, ParseError> {
+    let mut seen = [false; 10];
+    let cups = input
+        .char_indices()
+        .map(|(i, e)| match e.to_digit(10) {
+            Some(cup) if cup > 0 && !seen[cup as usize] => {
+                seen[cup as usize] = true;
+                Ok(cup as usize)
+            }
+            _ => Err(ParseError::at(
+                input,
+                &input[i..],
+                "a distinct cup label 1-9",
+            )),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if cups.len() == 9 {
+        Ok(cups)
+    } else {
+        Err(ParseError::end_of(input, "9 cup labels"))
+    }
 }
 
 fn iter(cups: &mut [usize], times: usize) {
@@ -12,7 +38,7 @@ fn iter(cups: &mut [usize], times: usize) {
         let taken_1 = cups[current] % cups.len();
         let taken_2 = cups[taken_1] % cups.len();
         let taken_3 = cups[taken_2] % cups.len();
-        let taken = vec![taken_1, taken_2, taken_3];
+        let taken = [taken_1, taken_2, taken_3];
         cups[current] = cups[taken_3] % cups.len();
 
         // target is decremented from current and not in taken
@@ -35,16 +61,21 @@ fn iter(cups: &mut [usize], times: usize) {
 
         current = cups[current] % cups.len();
     });
+
+    // so the next call carries on from here
+    cups[0] = current;
 }
 
 fn iter2(cups: &mut [usize], times: usize) {
     let mut current = cups[0];
 
+    let mut ticker = Ticker::new(23, Some(times as u64));
     (0..times).for_each(|_| {
+        ticker.tick();
         let taken_1 = cups[current] % cups.len();
         let taken_2 = cups[taken_1] % cups.len();
         let taken_3 = cups[taken_2] % cups.len();
-        let taken = vec![taken_1, taken_2, taken_3];
+        let taken = [taken_1, taken_2, taken_3];
         cups[current] = cups[taken_3] % cups.len();
 
         // target is decremented from current and not in taken
@@ -77,31 +108,39 @@ fn print_state(input: &[usize], start: usize, count: usize) -> usize {
     })
 }
 
-#[aoc(day23, part1)]
-pub fn solve_p1(input: &[usize]) -> usize {
+/// The circle as a linked list: `cups[label]` is the label of the next cup clockwise, and
+/// `cups[0]` is the current cup.
+fn circle(input: &[usize]) -> Vec<usize> {
     let mut cups = (0..10).collect::<Vec<_>>();
     cups[0] = input[0];
 
     (0..input.len() - 1).for_each(|i| {
-        let node = input[i] as usize;
-        let next = input[i + 1] as usize;
+        let node = input[i];
+        let next = input[i + 1];
         cups[node] = next;
-        cups[next] = input[0] as usize;
+        cups[next] = input[0];
     });
 
+    cups
+}
+
+#[aoc(day23, part1)]
+pub fn solve_p1(input: &[usize]) -> Answer {
+    let mut cups = circle(input);
+
     iter(&mut cups, 100);
 
-    print_state(&cups, cups[1], 8)
+    print_state(&cups, cups[1], 8).into()
 }
 
 #[aoc(day23, part2)]
-pub fn solve_p2(input: &[usize]) -> usize {
+pub fn solve_p2(input: &[usize]) -> Answer {
     let mut cups = (1..=1000000).collect::<Vec<_>>();
     cups[0] = input[0];
 
     (0..input.len() - 1).for_each(|i| {
-        let node = input[i] as usize;
-        let next = input[i + 1] as usize;
+        let node = input[i];
+        let next = input[i + 1];
         cups[node] = next;
         cups[next] = 10;
     });
@@ -110,20 +149,62 @@ pub fn solve_p2(input: &[usize]) -> usize {
 
     let a1 = cups[1];
     let a2 = cups[a1];
-    a1 * a2
+    (Exact::from(a1 as u64) * Exact::from(a2 as u64)).into()
+}
+
+/// The circle clockwise from the current cup, which is bracketed.
+fn show(cups: &[usize]) -> String {
+    let mut cup = cups[0];
+    let mut out = format!("({})", cup);
+    for _ in 1..9 {
+        cup = cups[cup];
+        out += &format!(" {}", cup);
+    }
+    out
+}
+
+/// The part 1 circle before the first move and after each of `moves` moves.
+pub fn moves(input: &[usize], moves: usize) -> Vec<String> {
+    let mut cups = circle(input);
+    let mut out = vec![show(&cups)];
+    for _ in 0..moves {
+        iter(&mut cups, 1);
+        out.push(show(&cups));
+    }
+    out
+}
+
+pub struct Day23;
+
+impl Puzzle for Day23 {
+    const DAY: u8 = 23;
+
+    type Input = Vec<usize>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    fn p1_wrapper(input: &str) -> usize {
-        let parsed = input_generator(input);
+    fn p1_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p1(&parsed)
     }
 
-    fn p2_wrapper(input: &str) -> usize {
-        let parsed = input_generator(input);
+    fn p2_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p2(&parsed)
     }
 
@@ -132,6 +213,19 @@ mod test {
         assert_eq!(p1_wrapper("389125467"), 67384529);
     }
 
+    #[test]
+    fn test_moves() {
+        let cups = input_generator("389125467").unwrap();
+        assert_eq!(
+            moves(&cups, 2),
+            [
+                "(3) 8 9 1 2 5 4 6 7",
+                "(2) 8 9 1 5 4 6 7 3",
+                "(5) 4 6 7 8 9 1 3 2"
+            ]
+        );
+    }
+
     #[test]
     fn test_p2() {
         assert_eq!(p2_wrapper("389125467"), 149245887792);
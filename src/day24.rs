@@ -1,7 +1,15 @@
-use std::{collections::HashSet, str::FromStr};
+use std::{collections::HashSet, io::BufRead, str::FromStr};
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::answer::Answer;
+use crate::automaton::{Automaton, BirthSurvive, Hex};
+use crate::error::{Error, ParseError};
+use crate::frames::{history, Frame};
+use crate::grid::Grid;
+use crate::puzzle::Puzzle;
+use crate::stream;
+
 #[derive(Debug)]
 pub enum Direction {
     East,
@@ -13,7 +21,7 @@ pub enum Direction {
 }
 
 impl Direction {
-    fn apply(&self, (x, y): (i8, i8)) -> (i8, i8) {
+    fn apply(&self, (x, y): (i32, i32)) -> (i32, i32) {
         match self {
             Direction::East => (x + 1, y),
             Direction::Southeast => (x, y + 1),
@@ -41,20 +49,22 @@ impl FromStr for Direction {
     }
 }
 
-#[aoc_generator(day24)]
-pub fn input_generator(input: &str) -> Vec<Vec<Direction>> {
-    input
-        .lines()
-        .map(|line| {
-            line.split_inclusive(|e| e == 'e' || e == 'w')
-                .map(|i| i.parse().unwrap())
-                .collect()
+fn parse_line(line: &str) -> Result<Vec<Direction>, ParseError> {
+    line.split_inclusive(['e', 'w'])
+        .map(|i| {
+            i.parse()
+                .map_err(|_| ParseError::at(line, i, "`e`, `se`, `sw`, `w`, `nw` or `ne`"))
         })
         .collect()
 }
 
-fn get_initial_state(input: &[Vec<Direction>]) -> HashSet<(i8, i8)> {
-    let tiles: Vec<(i8, i8)> = input
+#[aoc_generator(day24)]
+pub fn input_generator(input: &str) -> Result<Vec<Vec<Direction>>, ParseError> {
+    stream::lines(input, parse_line)
+}
+
+fn get_initial_state(input: &[Vec<Direction>]) -> HashSet<(i32, i32)> {
+    let tiles: Vec<(i32, i32)> = input
         .iter()
         .map(|directions| {
             directions
@@ -75,72 +85,106 @@ fn get_initial_state(input: &[Vec<Direction>]) -> HashSet<(i8, i8)> {
 }
 
 #[aoc(day24, part1)]
-pub fn solve_p1(input: &[Vec<Direction>]) -> usize {
-    get_initial_state(input).len()
+pub fn solve_p1(input: &[Vec<Direction>]) -> Answer {
+    get_initial_state(input).len().into()
 }
 
-fn iter(tiles: HashSet<(i8, i8)>) -> HashSet<(i8, i8)> {
-    let neighbors = vec![
-        Direction::East,
-        Direction::Southeast,
-        Direction::Southwest,
-        Direction::West,
-        Direction::Northwest,
-        Direction::Northeast,
-    ];
-    let candidates = tiles
-        .iter()
-        .flat_map(|tile| {
-            neighbors
-                .iter()
-                .map(move |direction| direction.apply(*tile))
-        })
-        .collect::<HashSet<_>>();
+const BLACK: BirthSurvive = BirthSurvive {
+    birth: &[2],
+    survive: &[1, 2],
+};
+
+fn run(input: &HashSet<(i32, i32)>, times: usize) -> HashSet<(i32, i32)> {
+    Automaton::new(Hex, BLACK, input.iter().copied())
+        .run(times)
+        .live()
+        .clone()
+}
 
-    candidates
+#[aoc(day24, part2)]
+pub fn solve_p2(input: &[Vec<Direction>]) -> Answer {
+    let tiles = get_initial_state(input);
+
+    run(&tiles, 100).len().into()
+}
+
+/// One frame per generation. Hexes are drawn two columns wide with alternate rows offset
+/// by one, so row `y` holds column `2x + y`; the odd columns in between are blank.
+pub fn frames(input: &[Vec<Direction>], generations: usize) -> Vec<Frame> {
+    let history = history(
+        Automaton::new(Hex, BLACK, get_initial_state(input)),
+        generations,
+    );
+
+    let rows = history.iter().flatten().map(|&(_, y)| y);
+    let cols = history.iter().flatten().map(|&(x, y)| 2 * x + y);
+    let (top, bottom) = match (rows.clone().min(), rows.max()) {
+        (Some(top), Some(bottom)) => (top, bottom),
+        _ => return vec![],
+    };
+    let (left, right) = (cols.clone().min().unwrap(), cols.max().unwrap());
+
+    history
         .iter()
-        .filter(|tile| {
-            let nearby = neighbors
-                .iter()
-                .map(|direction| direction.apply(**tile))
-                .filter(|tile| tiles.contains(tile))
-                .count();
-
-            if tiles.contains(tile) {
-                // black tile
-                !(nearby == 0 || nearby > 2)
-            } else {
-                // white tile
-                nearby == 2
-            }
+        .enumerate()
+        .map(|(generation, black)| Frame {
+            name: format!("gen{:03}", generation),
+            generation,
+            image: Grid::from_fn(
+                (bottom - top + 1) as usize,
+                (right - left + 1) as usize,
+                |(row, col)| {
+                    let y = top + row as i32;
+                    let twice_x = left + col as i32 - y;
+                    if twice_x.rem_euclid(2) != 0 {
+                        0
+                    } else if black.contains(&(twice_x / 2, y)) {
+                        2
+                    } else {
+                        1
+                    }
+                },
+            ),
+            palette: &[' ', '.', '#'],
         })
-        .copied()
         .collect()
 }
 
-fn run(input: &HashSet<(i8, i8)>, times: u16) -> HashSet<(i8, i8)> {
-    let tiles = input.clone();
-    (0..times).fold(tiles, |tiles, _| iter(tiles))
-}
+pub struct Day24;
 
-#[aoc(day24, part2)]
-pub fn solve_p2(input: &[Vec<Direction>]) -> usize {
-    let tiles = get_initial_state(input);
+impl Puzzle for Day24 {
+    const DAY: u8 = 24;
+
+    type Input = Vec<Vec<Direction>>;
 
-    run(&tiles, 100).len()
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn read(reader: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        stream::read_lines(reader, parse_line)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
 
-    fn p1_wrapper(input: &str) -> usize {
-        let parsed = input_generator(input);
+    fn p1_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         solve_p1(&parsed)
     }
 
-    fn p2_wrapper(input: &str) -> usize {
-        let parsed = input_generator(input);
+    fn p2_wrapper(input: &str) -> Answer {
+        let parsed = input_generator(input).unwrap();
         let tiles = get_initial_state(&parsed);
 
         assert_eq!(run(&tiles, 1).len(), 15);
@@ -1,39 +1,69 @@
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::answer::Answer;
+use crate::error::{parse_at, ParseError};
+use crate::number_theory;
+use crate::puzzle::Puzzle;
+
 #[aoc_generator(day25)]
-pub fn input_generator(input: &str) -> (usize, usize) {
-    let input = input
-        .lines()
-        .map(|i| i.parse().unwrap())
-        .collect::<Vec<_>>();
-    (input[0], input[1])
+pub fn input_generator(input: &str) -> Result<(usize, usize), ParseError> {
+    let mut lines = input.lines();
+    let mut next_key = |name: &str| {
+        let line = lines
+            .next()
+            .ok_or_else(|| ParseError::end_of(input, format!("the {} public key", name)))?;
+        match parse_at(input, line, "a public key")? {
+            key @ 1..=MAX_KEY => Ok(key),
+            _ => Err(ParseError::at(
+                input,
+                line,
+                format!("a public key from 1 to {}", MAX_KEY),
+            )),
+        }
+    };
+
+    Ok((next_key("card")?, next_key("door")?))
 }
 
 #[aoc(day25, part1)]
-pub fn solve_p1((card_key, door_key): &(usize, usize)) -> usize {
-    // let card_loops = solve_for_loops(7, *card_key);
+pub fn solve_p1((card_key, door_key): &(usize, usize)) -> Answer {
     let door_loops = solve_for_loops(7, *door_key);
-    // println!("{} {}", card_loops, door_loops);
-    // let enc_key = transform(*card_key, door_loops);
-    // assert_eq!(enc_key, transform(*card_key, door_loops));
-    // enc_key
-    transform(*card_key, door_loops)
+    trace!(25, "loops", door = door_loops);
+    transform(*card_key, door_loops).into()
 }
 
+const MODULUS: usize = 20201227;
+/// 7 generates every non-zero remainder, so each of these is some loop size's public key.
+const MAX_KEY: usize = MODULUS - 1;
+
 fn transform(subject: usize, loops: usize) -> usize {
-    (0..loops).fold(1, |val, _| val * subject % 20201227)
+    number_theory::pow_mod(subject as u64, loops as u64, MODULUS as u64) as usize
 }
 
 fn solve_for_loops(subject: usize, public: usize) -> usize {
-    let mut current = 1;
-    for i in 0.. {
-        current *= subject;
-        current %= 20201227;
-        if current == public {
-            return i + 1;
-        }
+    number_theory::discrete_log(subject as u64, public as u64, MODULUS as u64)
+        .unwrap_or_else(|| panic!("no loop size gives the public key {}", public)) as usize
+}
+
+pub struct Day25;
+
+impl Puzzle for Day25 {
+    const DAY: u8 = 25;
+    const PARTS: u8 = 1;
+
+    type Input = (usize, usize);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(_input: &Self::Input) -> Answer {
+        unreachable!("day 25 has only one part")
     }
-    0
 }
 
 #[cfg(test)]
@@ -48,4 +78,11 @@ mod test {
         assert_eq!(transform(17807724, 8), 14897079);
         assert_eq!(solve_p1(&(5764801, 17807724)), 14897079);
     }
+
+    #[test]
+    fn test_parse() {
+        assert!(input_generator("0\n0").is_err());
+        assert!(input_generator("5764801\n20201227").is_err());
+        assert_eq!(input_generator("1\n20201226"), Ok((1, 20201226)));
+    }
 }
@@ -1,39 +1,64 @@
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::answer::Answer;
+use crate::checked;
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::puzzle::Puzzle;
+
 #[aoc_generator(day3)]
-pub fn input_generator(input: &str) -> Vec<Vec<u8>> {
-    // let input = "..##.......\n#...#...#..\n.#....#..#.\n..#.#...#.#\n.#...##..#.\n..#.##.....\n.#.#.#....#\n.#........#\n#.##...#...\n#...##....#\n.#..#...#.#";
-    input
-        .lines()
-        .map(|e| {
-            e.chars()
-                .map(|c| if c == '#' { 1 } else { 0 })
-                .collect::<Vec<_>>()
-        })
-        .collect()
+pub fn input_generator(input: &str) -> Result<Grid<bool>, ParseError> {
+    Grid::parse(
+        input,
+        |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        },
+        "`#` or `.`",
+    )
 }
 
 #[aoc(day3, part1)]
-pub fn solve_p1(input: &[Vec<u8>]) -> u64 {
-    ski(input, 3, 1)
+pub fn solve_p1(input: &Grid<bool>) -> Answer {
+    ski(input, 3, 1).into()
 }
 
-fn ski(mountain: &[Vec<u8>], x_slope: usize, y_slope: usize) -> u64 {
-    let mut x_index = 0;
-    let mut y_index = 0;
-    let mut acc = 0;
-    while y_index < mountain.len() {
-        let row = &mountain[y_index];
-        acc += row[x_index] as u64;
-        x_index += x_slope;
-        x_index %= row.len();
-        y_index += y_slope;
-    }
-
-    acc
+fn ski(mountain: &Grid<bool>, x_slope: usize, y_slope: usize) -> u64 {
+    (0..mountain.height())
+        .step_by(y_slope)
+        .enumerate()
+        .filter(|&(step, y)| mountain[(y, step * x_slope % mountain.width())])
+        .count() as u64
 }
 
 #[aoc(day3, part2)]
-pub fn solve_p2(input: &[Vec<u8>]) -> u64 {
-    ski(input, 1, 1) * ski(input, 3, 1) * ski(input, 5, 1) * ski(input, 7, 1) * ski(input, 1, 2)
+pub fn solve_p2(input: &Grid<bool>) -> Answer {
+    let slopes = [(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)];
+    checked::product(
+        3,
+        "tree product",
+        slopes.iter().map(|&(x, y)| ski(input, x, y)),
+    )
+    .into()
+}
+
+pub struct Day3;
+
+impl Puzzle for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Grid<bool>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
 }
@@ -1,55 +1,42 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, io::BufRead};
 
 use aoc_runner_derive::{aoc, aoc_generator};
-use nom::{
-    branch::alt, bytes::complete::tag, bytes::complete::take_till, character::complete::space1,
-    multi::separated_list0, number::complete::hex_u32, sequence::separated_pair, IResult,
-};
+use nom::{number::complete::hex_u32, IResult};
 
-#[aoc_generator(day4)]
-pub fn input_generator(input: &str) -> Vec<HashMap<String, String>> {
-    // let input = "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd\nbyr:1937 iyr:2017 cid:147 hgt:183cm\n\niyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884\nhcl:#cfa07d byr:1929\n\nhcl:#ae17e1 iyr:2013\neyr:2024\necl:brn pid:760753108 byr:1931\nhgt:179cm\n\nhcl:#cfa07d eyr:2025 pid:166559648\niyr:2011 ecl:brn hgt:59in";
-    // let input = "eyr:1972 cid:100\nhcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926\n\niyr:2019\nhcl:#602927 eyr:1967 hgt:170cm\necl:grn pid:012533040 byr:1946\n\nhcl:dab227 iyr:2012\necl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277\n\nhgt:59cm ecl:zzz\neyr:2038 hcl:74454a iyr:2023\npid:3556412378 byr:2007";
-    // let input = "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980\nhcl:#623a2f\n\neyr:2029 ecl:blu cid:129 byr:1989\niyr:2014 pid:896056539 hcl:#a97842 hgt:165cm\n\nhcl:#888785\nhgt:164cm byr:2001 iyr:2015 cid:88\npid:545766238 ecl:hzl\neyr:2022\n\niyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719";
-
-    let fixed_input = input
-        .replace("\n\n", "\t")
-        .replace("\n", " ")
-        .replace("\t", "\n");
-
-    let parsed = fixed_input
-        .lines()
-        .map(|line| {
-            let res: IResult<_, _> = separated_list0(
-                space1,
-                alt((
-                    separated_pair(tag("byr"), tag(":"), take_till(|a| a == ' ' || a == '\n')),
-                    separated_pair(tag("iyr"), tag(":"), take_till(|a| a == ' ' || a == '\n')),
-                    separated_pair(tag("eyr"), tag(":"), take_till(|a| a == ' ' || a == '\n')),
-                    separated_pair(tag("hgt"), tag(":"), take_till(|a| a == ' ' || a == '\n')),
-                    separated_pair(tag("hcl"), tag(":"), take_till(|a| a == ' ' || a == '\n')),
-                    separated_pair(tag("ecl"), tag(":"), take_till(|a| a == ' ' || a == '\n')),
-                    separated_pair(tag("pid"), tag(":"), take_till(|a| a == ' ' || a == '\n')),
-                    separated_pair(tag("cid"), tag(":"), take_till(|a| a == ' ' || a == '\n')),
-                )),
-            )(line);
-            res.unwrap()
-                .1
-                .iter()
-                .map(|(a, b)| ((*a).into(), (*b).into()))
-                .collect::<HashMap<_, _>>()
+use crate::answer::Answer;
+use crate::error::{Error, ParseError};
+use crate::puzzle::Puzzle;
+use crate::stream;
+
+const FIELDS: [&str; 8] = ["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"];
+
+fn parse_passport(passport: &str) -> Result<HashMap<String, String>, ParseError> {
+    passport
+        .split_whitespace()
+        .map(|field| {
+            let (key, value) = field
+                .split_once(':')
+                .filter(|(key, _)| FIELDS.contains(key))
+                .ok_or_else(|| {
+                    ParseError::at(passport, field, "a passport field like `byr:1937`")
+                })?;
+            Ok((key.into(), value.into()))
         })
-        .collect::<Vec<_>>();
+        .collect()
+}
 
-    parsed
+#[aoc_generator(day4)]
+pub fn input_generator(input: &str) -> Result<Vec<HashMap<String, String>>, ParseError> {
+    stream::blocks(input, parse_passport)
 }
 
 #[aoc(day4, part1)]
-pub fn solve_p1(input: &[HashMap<String, String>]) -> u64 {
+pub fn solve_p1(input: &[HashMap<String, String>]) -> Answer {
     input
         .iter()
         .map(|passport| if validate_fields(passport) { 1 } else { 0 })
-        .sum()
+        .sum::<u64>()
+        .into()
 }
 
 fn validate_fields(input: &HashMap<String, String>) -> bool {
@@ -64,11 +51,12 @@ fn validate_fields(input: &HashMap<String, String>) -> bool {
 }
 
 #[aoc(day4, part2)]
-pub fn solve_p2(input: &[HashMap<String, String>]) -> u64 {
+pub fn solve_p2(input: &[HashMap<String, String>]) -> Answer {
     input
         .iter()
         .map(|passport| if validate_fields2(passport) { 1 } else { 0 })
-        .sum()
+        .sum::<u64>()
+        .into()
 }
 
 fn validate_fields2(input: &HashMap<String, String>) -> bool {
@@ -84,7 +72,7 @@ fn validate_fields2(input: &HashMap<String, String>) -> bool {
 
 fn check_byr(entry: Option<(&String, &String)>) -> bool {
     if let Some((_, v)) = entry {
-        v.parse::<i32>().unwrap() >= 1920 && v.parse::<i32>().unwrap() <= 2002
+        matches!(v.parse::<i32>(), Ok(year) if (1920..=2002).contains(&year))
     } else {
         false
     }
@@ -92,7 +80,7 @@ fn check_byr(entry: Option<(&String, &String)>) -> bool {
 
 fn check_iyr(entry: Option<(&String, &String)>) -> bool {
     if let Some((_, v)) = entry {
-        v.parse::<i32>().unwrap() >= 2010 && v.parse::<i32>().unwrap() <= 2020
+        matches!(v.parse::<i32>(), Ok(year) if (2010..=2020).contains(&year))
     } else {
         false
     }
@@ -100,7 +88,7 @@ fn check_iyr(entry: Option<(&String, &String)>) -> bool {
 
 fn check_eyr(entry: Option<(&String, &String)>) -> bool {
     if let Some((_, v)) = entry {
-        v.parse::<i32>().unwrap() >= 2020 && v.parse::<i32>().unwrap() <= 2030
+        matches!(v.parse::<i32>(), Ok(year) if (2020..=2030).contains(&year))
     } else {
         false
     }
@@ -142,7 +130,7 @@ fn check_hcl(entry: Option<(&String, &String)>) -> bool {
 }
 
 fn check_ecl(entry: Option<(&String, &String)>) -> bool {
-    let opts = vec!["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+    let opts = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
     if let Some((_, v)) = entry {
         opts.contains(&(*v).as_str())
     } else {
@@ -157,3 +145,27 @@ fn check_pid(entry: Option<(&String, &String)>) -> bool {
         false
     }
 }
+
+pub struct Day4;
+
+impl Puzzle for Day4 {
+    const DAY: u8 = 4;
+
+    type Input = Vec<HashMap<String, String>>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn read(reader: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        stream::read_blocks(reader, parse_passport)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
+}
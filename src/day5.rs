@@ -1,28 +1,64 @@
+use std::io::BufRead;
+
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::answer::Answer;
+use crate::error::{Error, ParseError};
+use crate::puzzle::Puzzle;
+use crate::stream;
+
+fn parse_line(line: &str) -> Result<u16, ParseError> {
+    if line.len() != 10 {
+        return Err(ParseError::at(line, line, "a 10 character boarding pass"));
+    }
+
+    line.char_indices().try_fold(0, |seat, (i, c)| match c {
+        'B' | 'R' => Ok(seat << 1 | 1),
+        'F' | 'L' => Ok(seat << 1),
+        _ => Err(ParseError::at(line, &line[i..], "`F`, `B`, `L` or `R`")),
+    })
+}
+
 #[aoc_generator(day5)]
-pub fn input_generator(input: &str) -> Vec<u16> {
-    input
-        .replace("B", "1")
-        .replace("F", "0")
-        .replace("R", "1")
-        .replace("L", "0")
-        .lines()
-        .map(|num| u16::from_str_radix(num, 2).unwrap())
-        .collect()
+pub fn input_generator(input: &str) -> Result<Vec<u16>, ParseError> {
+    stream::lines(input, parse_line)
 }
 
 #[aoc(day5, part1)]
-pub fn solve_p1(input: &[u16]) -> u16 {
-    input.iter().fold(0, |acc, x| acc.max(*x))
+pub fn solve_p1(input: &[u16]) -> Answer {
+    input.iter().fold(0, |acc, x| acc.max(*x)).into()
 }
 
 #[aoc(day5, part2)]
-pub fn solve_p2(input: &[u16]) -> u16 {
+pub fn solve_p2(input: &[u16]) -> Answer {
     let max = input.iter().fold(0, |acc, x| acc.max(*x));
     let min = input.iter().fold(max, |acc, x| acc.min(*x));
     let seat = (min..=max)
         .filter(|seat| !input.contains(seat))
         .collect::<Vec<_>>();
-    seat[0]
+    seat[0].into()
+}
+
+pub struct Day5;
+
+impl Puzzle for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = Vec<u16>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn read(reader: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        stream::read_lines(reader, parse_line)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
 }
@@ -2,17 +2,28 @@ use std::collections::HashSet;
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::answer::Answer;
+use crate::error::ParseError;
+use crate::puzzle::Puzzle;
+
 #[aoc_generator(day6)]
-pub fn input_generator(input: &str) -> Vec<Vec<Vec<u8>>> {
-    // let input = "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb";
+pub fn input_generator(input: &str) -> Result<Vec<Vec<Vec<u8>>>, ParseError> {
     input
         .split("\n\n")
-        .map(|votes| votes.lines().map(|line| line.as_bytes().to_vec()).collect())
+        .map(|votes| {
+            votes
+                .lines()
+                .map(|line| match line.find(|c: char| !c.is_ascii_lowercase()) {
+                    Some(i) => Err(ParseError::at(input, &line[i..], "a question `a`-`z`")),
+                    None => Ok(line.as_bytes().to_vec()),
+                })
+                .collect()
+        })
         .collect()
 }
 
 #[aoc(day6, part1)]
-pub fn solve_p1(input: &[Vec<Vec<u8>>]) -> usize {
+pub fn solve_p1(input: &[Vec<Vec<u8>>]) -> Answer {
     input
         .iter()
         .map(|set| {
@@ -25,11 +36,12 @@ pub fn solve_p1(input: &[Vec<Vec<u8>>]) -> usize {
                 })
                 .len()
         })
-        .sum()
+        .sum::<usize>()
+        .into()
 }
 
 #[aoc(day6, part2)]
-pub fn solve_p2(input: &[Vec<Vec<u8>>]) -> usize {
+pub fn solve_p2(input: &[Vec<Vec<u8>>]) -> Answer {
     input
         .iter()
         .map(|set| {
@@ -44,5 +56,26 @@ pub fn solve_p2(input: &[Vec<Vec<u8>>]) -> usize {
                 .filter(|vote| set.iter().all(|voter| voter.contains(*vote)))
                 .count()
         })
-        .sum()
+        .sum::<usize>()
+        .into()
+}
+
+pub struct Day6;
+
+impl Puzzle for Day6 {
+    const DAY: u8 = 6;
+
+    type Input = Vec<Vec<Vec<u8>>>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
 }
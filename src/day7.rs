@@ -1,33 +1,52 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use aoc_runner_derive::{aoc, aoc_generator};
-use nom::{regex::Regex, regexp::str::re_captures, IResult};
+use itertools::Itertools;
+use nom::regex::Regex;
+
+use crate::answer::Answer;
+use crate::checked;
+use crate::error::{parse_at, ParseError};
+use crate::explain::Step;
+use crate::puzzle::Puzzle;
 
 #[aoc_generator(day7)]
-pub fn input_generator(input: &str) -> HashMap<String, Vec<(u8, String)>> {
-    // let input = "light red bags contain 1 bright white bag, 2 muted yellow bags.\ndark orange bags contain 3 bright white bags, 4 muted yellow bags.\nbright white bags contain 1 shiny gold bag.\nmuted yellow bags contain 2 shiny gold bags, 9 faded blue bags.\nshiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.\ndark olive bags contain 3 faded blue bags, 4 dotted black bags.\nvibrant plum bags contain 5 faded blue bags, 6 dotted black bags.\nfaded blue bags contain no other bags.\ndotted black bags contain no other bags.";
-    // let input = "shiny gold bags contain 2 dark red bags.\ndark red bags contain 2 dark orange bags.\ndark orange bags contain 2 dark yellow bags.\ndark yellow bags contain 2 dark green bags.\ndark green bags contain 2 dark blue bags.\ndark blue bags contain 2 dark violet bags.\ndark violet bags contain no other bags.";
-    let parser = Regex::new("(\\w+ \\w+) bags contain (?:(?:(\\d) (\\w+ \\w+) bags?(?:, (\\d) (\\w+ \\w+) bags?(?:, (\\d) (\\w+ \\w+) bags?(?:, (\\d) (\\w+ \\w+) bags?)?)?)?)|(?:no other bags))\\.").unwrap();
-    let res: IResult<_, _> = re_captures(parser)(input);
-    let (_, lines) = res.unwrap();
-
-    lines
-        .iter()
+pub fn input_generator(input: &str) -> Result<HashMap<String, Vec<(u8, String)>>, ParseError> {
+    let parser = Regex::new("^(\\w+ \\w+) bags contain (?:(?:(\\d) (\\w+ \\w+) bags?(?:, (\\d) (\\w+ \\w+) bags?(?:, (\\d) (\\w+ \\w+) bags?(?:, (\\d) (\\w+ \\w+) bags?)?)?)?)|(?:no other bags))\\.$").unwrap();
+
+    input
+        .lines()
         .map(|line| {
+            let captures = parser.captures(line).ok_or_else(|| {
+                ParseError::at(
+                    input,
+                    line,
+                    "a rule like `faded blue bags contain no other bags.`",
+                )
+            })?;
+            let line = captures
+                .iter()
+                .flatten()
+                .map(|m| m.as_str())
+                .collect::<Vec<_>>();
             let stuff = line
                 .chunks_exact(2)
                 .skip(1)
-                .map(|chunk| (chunk[0].parse::<u8>().unwrap(), chunk[1].to_owned()))
-                .collect::<Vec<_>>();
-            (line[1].to_owned(), stuff)
+                .map(|chunk| {
+                    Ok((
+                        parse_at(input, chunk[0], "a bag count")?,
+                        chunk[1].to_owned(),
+                    ))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((line[1].to_owned(), stuff))
         })
         .collect()
 }
 
-#[aoc(day7, part1)]
-pub fn solve_p1(input: &HashMap<String, Vec<(u8, String)>>) -> usize {
-    let mut candidate_bags = HashSet::new();
-    let inverted_map = input.iter().fold(HashMap::new(), |mut acc, (k, v)| {
+/// The bags each bag can be put in directly.
+fn invert(input: &HashMap<String, Vec<(u8, String)>>) -> HashMap<&String, Vec<String>> {
+    input.iter().fold(HashMap::new(), |mut acc, (k, v)| {
         v.iter().for_each(|(_, id)| {
             if !acc.contains_key(id) {
                 acc.insert(id, Vec::new());
@@ -37,7 +56,13 @@ pub fn solve_p1(input: &HashMap<String, Vec<(u8, String)>>) -> usize {
             }
         });
         acc
-    });
+    })
+}
+
+#[aoc(day7, part1)]
+pub fn solve_p1(input: &HashMap<String, Vec<(u8, String)>>) -> Answer {
+    let mut candidate_bags = HashSet::new();
+    let inverted_map = invert(input);
 
     candidate_bags.insert("shiny gold".into());
 
@@ -63,18 +88,98 @@ pub fn solve_p1(input: &HashMap<String, Vec<(u8, String)>>) -> usize {
         }
     }
 
-    candidate_bags.len() - 1
+    (candidate_bags.len() - 1).into()
 }
 
 fn contained_bag_count(bags: &HashMap<String, Vec<(u8, String)>>, bag: String) -> u64 {
-    bags.get(&bag)
-        .unwrap()
-        .iter()
-        .map(|(cnt, inner_bag)| *cnt as u64 * (1 + contained_bag_count(bags, inner_bag.clone())))
-        .sum()
+    checked::sum(
+        7,
+        "bag count",
+        bags.get(&bag).unwrap().iter().map(|(cnt, inner_bag)| {
+            let inner = contained_bag_count(bags, inner_bag.clone());
+            checked::mul(
+                7,
+                "bag count",
+                *cnt as u64,
+                checked::add(7, "bag count", 1, inner),
+            )
+        }),
+    )
 }
 
 #[aoc(day7, part2)]
-pub fn solve_p2(input: &HashMap<String, Vec<(u8, String)>>) -> u64 {
-    contained_bag_count(input, "shiny gold".into())
+pub fn solve_p2(input: &HashMap<String, Vec<(u8, String)>>) -> Answer {
+    contained_bag_count(input, "shiny gold".into()).into()
+}
+
+/// Every bag that can hold a shiny gold one, with the shortest chain of bags down to it.
+fn explain_p1(input: &HashMap<String, Vec<(u8, String)>>) -> Vec<Step> {
+    let inverted_map = invert(input);
+    let gold = "shiny gold".to_owned();
+    let mut chains = HashMap::new();
+    let mut queue = VecDeque::new();
+    queue.push_back((&gold, vec![&gold]));
+
+    while let Some((bag, chain)) = queue.pop_front() {
+        for outer in inverted_map.get(bag).into_iter().flatten().sorted() {
+            if !chains.contains_key(outer) {
+                chains.insert(outer, chain.clone());
+                let mut chain = chain.clone();
+                chain.insert(0, outer);
+                queue.push_back((outer, chain));
+            }
+        }
+    }
+
+    let mut chains = chains.into_iter().collect::<Vec<_>>();
+    chains.sort_by_key(|(bag, chain)| (chain.len(), *bag));
+    vec![chains.into_iter().fold(
+        Step::new("bags that can hold shiny gold, and what they hold it in"),
+        |step, (bag, chain)| step.fact(bag, chain.iter().join(" > ")),
+    )]
+}
+
+fn explain_p2(input: &HashMap<String, Vec<(u8, String)>>) -> Vec<Step> {
+    let contents = input.get("shiny gold").into_iter().flatten().fold(
+        Step::new("what shiny gold holds directly, and what each of those holds"),
+        |step, (count, bag)| {
+            step.fact(
+                format!("{} {}", count, bag),
+                format!(
+                    "{} bags inside each",
+                    contained_bag_count(input, bag.clone())
+                ),
+            )
+        },
+    );
+
+    vec![contents]
+}
+
+pub struct Day7;
+
+impl Puzzle for Day7 {
+    const DAY: u8 = 7;
+
+    type Input = HashMap<String, Vec<(u8, String)>>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
+
+    fn explain(input: &Self::Input, part: u8) -> Option<Vec<Step>> {
+        match part {
+            1 => Some(explain_p1(input)),
+            2 => Some(explain_p2(input)),
+            _ => None,
+        }
+    }
 }
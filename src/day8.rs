@@ -1,14 +1,22 @@
-use std::{collections::HashSet, fmt::Debug};
+use std::{collections::HashSet, fmt::Display, io::BufRead};
 
 use aoc_runner_derive::{aoc, aoc_generator};
 use nom::{
+    branch::alt,
     bytes::complete::tag,
-    bytes::complete::{is_a, take},
-    multi::separated_list0,
-    sequence::{terminated, tuple},
+    character::complete::{digit1, one_of},
+    combinator::{all_consuming, map_res, recognize},
+    sequence::{pair, terminated},
     IResult,
 };
 
+use crate::answer::Answer;
+use crate::checked;
+use crate::error::{Error, ParseError};
+use crate::explain::Step;
+use crate::puzzle::Puzzle;
+use crate::stream;
+
 #[derive(Debug, Clone, Copy)]
 pub enum Op {
     Nop(i16),
@@ -27,23 +35,31 @@ impl From<(&str, i16)> for Op {
     }
 }
 
-#[aoc_generator(day8)]
-pub fn input_generator(input: &str) -> Vec<Op> {
-    //let input = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
-    let res: IResult<_, _> = separated_list0(
-        tag("\n"),
-        tuple((terminated(take(3usize), tag(" ")), is_a("+-1234567890"))),
-    )(input);
+fn parse_op(input: &str) -> IResult<&str, &str> {
+    terminated(alt((tag("acc"), tag("jmp"), tag("nop"))), tag(" "))(input)
+}
+
+fn parse_offset(input: &str) -> IResult<&str, i16> {
+    all_consuming(map_res(recognize(pair(one_of("+-"), digit1)), |s: &str| {
+        s.parse()
+    }))(input)
+}
 
-    let (_, txt) = res.unwrap();
+fn parse_line(line: &str) -> Result<Op, ParseError> {
+    let (rest, op) =
+        parse_op(line).map_err(|e| ParseError::from_nom(line, e, "`acc`, `jmp` or `nop`"))?;
+    let (_, val) = parse_offset(rest)
+        .map_err(|e| ParseError::from_nom(line, e, "a signed offset like `+4`"))?;
 
-    txt.iter()
-        .map(|(a, b)| Op::from((*a, b.parse::<i16>().unwrap())))
-        .collect()
+    Ok(Op::from((op, val)))
 }
 
-#[aoc(day8, part1)]
-pub fn solve_p1(input: &[Op]) -> State {
+#[aoc_generator(day8)]
+pub fn input_generator(input: &str) -> Result<Vec<Op>, ParseError> {
+    stream::lines(input, parse_line)
+}
+
+fn run(input: &[Op]) -> State {
     let mut seen_lines = HashSet::new();
     let mut curr = 0;
     let mut acc = 0;
@@ -56,11 +72,11 @@ pub fn solve_p1(input: &[Op]) -> State {
         let op = input[curr];
         match op {
             Op::Acc(val) => {
-                acc += val;
+                acc = checked::add(8, "accumulator", acc, val);
                 curr += 1;
             }
             Op::Jmp(val) => {
-                curr += val as usize;
+                curr = checked::offset(8, "jump", curr, val as isize);
             }
             Op::Nop(_) => {
                 curr += 1;
@@ -77,14 +93,33 @@ pub struct State {
     acc: i16,
 }
 
-impl std::fmt::Display for State {
+impl From<State> for Answer {
+    fn from(state: State) -> Self {
+        Answer::structured(vec![
+            ("index", state.index as i64),
+            ("acc", state.acc as i64),
+        ])
+    }
+}
+
+#[aoc(day8, part1)]
+pub fn solve_p1(input: &[Op]) -> Answer {
+    run(input).acc.into()
+}
+
+impl Display for Op {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Debug::fmt(self, f)
+        match self {
+            Op::Nop(val) => write!(f, "nop {:+}", val),
+            Op::Jmp(val) => write!(f, "jmp {:+}", val),
+            Op::Acc(val) => write!(f, "acc {:+}", val),
+        }
     }
 }
 
-#[aoc(day8, part2)]
-pub fn solve_p2(input: &[Op]) -> State {
+/// Flips one `jmp` or `nop` at a time until the program runs off the end, returning which
+/// one it was and how the fixed program ends.
+fn repair(input: &[Op]) -> (usize, Op, State) {
     let mut asm = input.to_owned();
     let mut curr = 0;
     let mut state;
@@ -104,7 +139,7 @@ pub fn solve_p2(input: &[Op]) -> State {
             }
         }
 
-        state = solve_p1(&asm);
+        state = run(&asm);
         if state.index == input.len() {
             break;
         }
@@ -113,5 +148,60 @@ pub fn solve_p2(input: &[Op]) -> State {
         curr += 1;
     }
 
-    state
+    (curr, asm[curr], state)
+}
+
+#[aoc(day8, part2)]
+pub fn solve_p2(input: &[Op]) -> Answer {
+    repair(input).2.into()
+}
+
+fn explain_p1(input: &[Op]) -> Vec<Step> {
+    let state = run(input);
+    vec![Step::new("ran until an instruction came up a second time")
+        .fact(format!("instruction {}", state.index), input[state.index])
+        .fact("acc before it", state.acc)]
+}
+
+fn explain_p2(input: &[Op]) -> Vec<Step> {
+    let (index, flipped, state) = repair(input);
+    vec![
+        Step::new("flipped the first `jmp` or `nop` that lets the program finish").fact(
+            format!("instruction {}", index),
+            format!("{} became {}", input[index], flipped),
+        ),
+        Step::new("ran the fixed program off the end").fact("acc", state.acc),
+    ]
+}
+
+pub struct Day8;
+
+impl Puzzle for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = Vec<Op>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn read(reader: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        stream::read_lines(reader, parse_line)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
+
+    fn explain(input: &Self::Input, part: u8) -> Option<Vec<Step>> {
+        match part {
+            1 => Some(explain_p1(input)),
+            2 => Some(explain_p2(input)),
+            _ => None,
+        }
+    }
 }
@@ -1,11 +1,21 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, io::BufRead};
 
 use aoc_runner_derive::{aoc, aoc_generator};
 use itertools::Itertools;
 
+use crate::answer::Answer;
+use crate::checked;
+use crate::error::{parse_at, Error, ParseError};
+use crate::puzzle::{Implementation, Puzzle};
+use crate::stream;
+
+fn parse_line(line: &str) -> Result<u64, ParseError> {
+    parse_at(line, line, "an unsigned integer")
+}
+
 #[aoc_generator(day9)]
-pub fn input_generator(input: &str) -> Vec<u64> {
-    input.lines().map(|u| u.parse::<u64>().unwrap()).collect()
+pub fn input_generator(input: &str) -> Result<Vec<u64>, ParseError> {
+    stream::lines(input, parse_line)
 }
 
 fn is_predicated(e: u64, queue: &[u64]) -> bool {
@@ -14,8 +24,7 @@ fn is_predicated(e: u64, queue: &[u64]) -> bool {
     set.iter().tuple_combinations().any(|(a, b)| **a + **b == e)
 }
 
-#[aoc(day9, part1)]
-pub fn solve_p1(input: &[u64]) -> u64 {
+fn first_invalid(input: &[u64]) -> u64 {
     let mut queue = Vec::new();
 
     input.iter().take(25).for_each(|e| queue.push(*e));
@@ -33,25 +42,85 @@ pub fn solve_p1(input: &[u64]) -> u64 {
         .sum()
 }
 
+#[aoc(day9, part1)]
+pub fn solve_p1(input: &[u64]) -> Answer {
+    first_invalid(input).into()
+}
+
 #[aoc(day9, part2)]
-pub fn solve_p2(input: &[u64]) -> u64 {
-    let target = solve_p1(input);
+pub fn solve_p2(input: &[u64]) -> Answer {
+    let target = first_invalid(input);
+
+    // with every number positive, growing the window from the end and shrinking it from the
+    // start visits the ranges in the same order as trying each start in turn
+    let (mut start, mut sum) = (0, 0);
+    for end in 0..input.len() {
+        sum = checked::add(9, "range sum", sum, input[end]);
+        while sum > target && start < end {
+            sum -= input[start];
+            start += 1;
+        }
+
+        if sum == target && start < end {
+            let window = &input[start..=end];
+            let (min, max) = (window.iter().min().unwrap(), window.iter().max().unwrap());
+            return checked::add(9, "weakness", *min, *max).into();
+        }
+    }
+    panic!("no range of at least two numbers sums to {}", target)
+}
+
+fn skip_take(input: &[u64]) -> Answer {
+    let target = first_invalid(input);
 
     let mut i = 0;
     loop {
         let mut len = 1;
-        while input.iter().skip(i).take(len).sum::<u64>() < target {
+        let range_sum = |len| checked::sum(9, "range sum", input.iter().skip(i).take(len).copied());
+        while range_sum(len) < target {
             len += 1;
         }
 
-        if input.iter().skip(i).take(len).sum::<u64>() == target {
+        if range_sum(len) == target {
             // do the thing
             let min = input.iter().skip(i).take(len).min().unwrap();
             let max = input.iter().skip(i).take(len).max().unwrap();
-            // println!("{} {}", min, max);
-            return min + max;
+            trace!(9, "range", start = i, len, min, max);
+            return checked::add(9, "weakness", *min, *max).into();
         }
 
         i += 1;
     }
 }
+
+pub struct Day9;
+
+impl Puzzle for Day9 {
+    const DAY: u8 = 9;
+
+    type Input = Vec<u64>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input_generator(input)
+    }
+
+    fn read(reader: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        stream::read_lines(reader, parse_line)
+    }
+
+    fn implementations() -> Vec<Implementation<Self::Input>> {
+        vec![Implementation {
+            part: 2,
+            name: "skip-take",
+            solve: |input| skip_take(input),
+        }]
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        solve_p1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        solve_p2(input)
+    }
+}
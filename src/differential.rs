@@ -0,0 +1,135 @@
+use std::ops::Range;
+
+use crate::{generate, Answer, Error, Puzzle};
+
+/// An implementation that disagreed with the oracle on a generated input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub day: u8,
+    pub part: u8,
+    pub seed: u64,
+    pub name: &'static str,
+    pub expected: Answer,
+    pub actual: Answer,
+}
+
+fn oracle_parts<P: Puzzle>() -> Vec<u8> {
+    let mut parts: Vec<u8> = P::implementations().iter().map(|i| i.part).collect();
+    parts.sort_unstable();
+    parts.dedup();
+    parts
+}
+
+fn check_puzzle<P: Puzzle>(
+    part: u8,
+    seeds: Range<u64>,
+    size: usize,
+) -> Result<Vec<Mismatch>, Error> {
+    let implementations: Vec<_> = P::implementations()
+        .into_iter()
+        .filter(|i| i.part == part)
+        .collect();
+    let (oracle, others) = match implementations.split_first() {
+        Some(split) => split,
+        None => return Ok(vec![]),
+    };
+
+    let mut mismatches = vec![];
+    for seed in seeds {
+        let input = P::parse(&generate::generate(P::DAY, seed, size)?)?;
+        let expected = (oracle.solve)(&input);
+
+        let answers = others
+            .iter()
+            .map(|i| (i.name, (i.solve)(&input)))
+            .chain(Some(("solver", P::solve_part(&input, part)?)));
+        for (name, actual) in answers {
+            if actual != expected {
+                mismatches.push(Mismatch {
+                    day: P::DAY,
+                    part,
+                    seed,
+                    name,
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+    }
+
+    Ok(mismatches)
+}
+
+/// Parts of `day` that have an oracle to check against.
+pub fn checked_parts(day: u8) -> Vec<u8> {
+    with_puzzle!(day, P => oracle_parts::<P>(), _ => vec![])
+}
+
+/// Solves the generated input for each of `seeds` with every implementation of `day`'s
+/// `part`, and with the part's solver, and reports each answer that differs from the
+/// oracle's. A part with no registered implementations has nothing to differ from.
+pub fn check(day: u8, part: u8, seeds: Range<u64>, size: usize) -> Result<Vec<Mismatch>, Error> {
+    with_puzzle!(day, P => check_puzzle::<P>(part, seeds, size), _ => Err(Error::UnknownDay(day)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{day1::Day1, solve, Implementation};
+
+    #[test]
+    fn test_check() {
+        assert_eq!(checked_parts(1), [1, 2]);
+        assert_eq!(checked_parts(9), [2]);
+        assert_eq!(checked_parts(3), []);
+
+        // day 15 part 2 is left to its own tests: thirty million turns, twice per seed
+        for (day, part) in [(1, 1), (1, 2), (9, 2), (15, 1)] {
+            assert_eq!(check(day, part, 0..20, 100), Ok(vec![]), "day {}", day);
+        }
+        assert_eq!(check(3, 1, 0..1, 10), Ok(vec![]));
+        assert_eq!(check(26, 1, 0..1, 10), Err(Error::UnknownDay(26)));
+    }
+
+    /// Day 1 with a part 1 that is always wrong.
+    struct Broken;
+
+    impl Puzzle for Broken {
+        const DAY: u8 = 1;
+
+        type Input = Vec<u32>;
+
+        fn parse(input: &str) -> Result<Self::Input, crate::ParseError> {
+            Day1::parse(input)
+        }
+
+        fn implementations() -> Vec<Implementation<Self::Input>> {
+            Day1::implementations()
+        }
+
+        fn part1(_: &Self::Input) -> Answer {
+            0.into()
+        }
+
+        fn part2(input: &Self::Input) -> Answer {
+            Day1::part2(input)
+        }
+    }
+
+    #[test]
+    fn test_mismatch() {
+        let mismatches = check_puzzle::<Broken>(1, 0..2, 10).unwrap();
+        assert_eq!(
+            mismatches
+                .iter()
+                .map(|m| (m.seed, m.name, m.actual.clone()))
+                .collect::<Vec<_>>(),
+            [(0, "solver", 0.into()), (1, "solver", 0.into())]
+        );
+        assert_eq!(
+            mismatches[0].expected,
+            solve(1, 1, &generate::generate(1, 0, 10).unwrap()).unwrap()
+        );
+        assert_eq!(check_puzzle::<Broken>(2, 0..2, 10), Ok(vec![]));
+    }
+}
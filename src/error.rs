@@ -0,0 +1,224 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(line: usize, column: usize, expected: impl Into<String>) -> Self {
+        ParseError {
+            line,
+            column,
+            expected: expected.into(),
+        }
+    }
+
+    /// Builds an error pointing at `fragment`, which must be a slice of `input`.
+    /// Anything else is reported at the end of `input`.
+    pub fn at(input: &str, fragment: &str, expected: impl Into<String>) -> Self {
+        let start = input.as_ptr() as usize;
+        let pos = fragment.as_ptr() as usize;
+        let offset = if pos >= start && pos <= start + input.len() {
+            pos - start
+        } else {
+            input.len()
+        };
+
+        ParseError::from_offset(input, offset, expected)
+    }
+
+    pub fn from_offset(input: &str, offset: usize, expected: impl Into<String>) -> Self {
+        let before = &input[..offset];
+        let line = bytecount::count(before.as_bytes(), b'\n') + 1;
+        let column = match before.rfind('\n') {
+            Some(nl) => before[nl + 1..].chars().count() + 1,
+            None => before.chars().count() + 1,
+        };
+
+        ParseError::new(line, column, expected)
+    }
+
+    /// Moves an error reported against a single line or block to where it starts, on
+    /// `line` of the whole input.
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line += line - 1;
+        self
+    }
+
+    pub fn end_of(input: &str, expected: impl Into<String>) -> Self {
+        ParseError::from_offset(input, input.len(), expected)
+    }
+
+    pub fn from_nom(
+        input: &str,
+        err: nom::Err<nom::error::Error<&str>>,
+        expected: impl Into<String>,
+    ) -> Self {
+        match err {
+            nom::Err::Error(e) | nom::Err::Failure(e) => ParseError::at(input, e.input, expected),
+            nom::Err::Incomplete(_) => ParseError::end_of(input, expected),
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    Parse(ParseError),
+    UnknownDay(u8),
+    UnknownPart {
+        day: u8,
+        part: u8,
+    },
+    Io(String),
+    NoFrames(u8),
+    Overflow {
+        day: u8,
+        operation: &'static str,
+    },
+    NoExplanation {
+        day: u8,
+        part: u8,
+    },
+    Cancelled(u8),
+    BudgetExceeded {
+        day: u8,
+        budget: u64,
+    },
+    Unsolvable {
+        day: u8,
+        part: u8,
+        reason: &'static str,
+    },
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Error::Parse(err)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Parse(err) => write!(f, "parse error at {}", err),
+            Error::UnknownDay(day) => write!(f, "no solution for day {}", day),
+            Error::UnknownPart { day, part } => {
+                write!(f, "no solution for day {} part {}", day, part)
+            }
+            Error::Io(err) => write!(f, "{}", err),
+            Error::NoFrames(day) => write!(f, "no frames for day {}", day),
+            Error::Overflow { day, operation } => {
+                write!(f, "arithmetic overflow in day {}: {}", day, operation)
+            }
+            Error::NoExplanation { day, part } => {
+                write!(f, "no explanation for day {} part {}", day, part)
+            }
+            Error::Cancelled(day) => write!(f, "day {} was cancelled", day),
+            Error::BudgetExceeded { day, budget } => {
+                write!(f, "day {} ran past its budget of {} steps", day, budget)
+            }
+            Error::Unsolvable { day, part, reason } => {
+                write!(f, "day {} part {} can't be solved: {}", day, part, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Parses `fragment` (a slice of `input`) with `FromStr`, reporting failures at its position.
+pub fn parse_at<T: std::str::FromStr>(
+    input: &str,
+    fragment: &str,
+    expected: &str,
+) -> Result<T, ParseError> {
+    fragment
+        .parse()
+        .map_err(|_| ParseError::at(input, fragment, expected))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        let input = "12\n34\nab5";
+        let err = parse_at::<u32>(input, &input[7..], "an integer").unwrap_err();
+        assert_eq!(err, ParseError::new(3, 2, "an integer"));
+        assert_eq!(err.to_string(), "line 3, column 2: expected an integer");
+
+        assert_eq!(ParseError::end_of(input, "more").line, 3);
+        assert_eq!(ParseError::end_of(input, "more").column, 4);
+    }
+
+    #[test]
+    fn test_generator() {
+        let err = crate::day8::input_generator("nop +0\nacc 1").unwrap_err();
+        assert_eq!(err, ParseError::new(2, 5, "a signed offset like `+4`"));
+
+        let err = crate::day12::input_generator("F10\nR45").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(2, 1, "a turn of 90, 180 or 270 degrees")
+        );
+
+        let err = crate::day13::input_generator("939\nx,x").unwrap_err();
+        assert_eq!(err, ParseError::new(2, 1, "at least one bus ID"));
+
+        let err = crate::day19::input_generator("0: 1\n\na").unwrap_err();
+        assert_eq!(err, ParseError::new(1, 4, "a rule that is defined"));
+        let err = crate::day19::input_generator("0: 1 | 2\n1: \"a\"\n\na").unwrap_err();
+        assert_eq!(err, ParseError::new(1, 8, "a rule that is defined"));
+        let err = crate::day19::input_generator("1: \"a\"\n\na").unwrap_err();
+        assert_eq!(err, ParseError::new(1, 7, "rule 0"));
+        let err =
+            crate::day19::input_generator("0: 1 2 | 3\n1: 0 3\n2: 3\n3: \"a\"\n\na").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(1, 4, "a rule that matches something before it repeats")
+        );
+        assert!(crate::day19::input_generator("0: 1 0 | 1\n1: \"a\"\n\na").is_ok());
+        assert_eq!(
+            crate::solve(19, 2, "0: 1\n1: \"a\"\n\na"),
+            Err(crate::Error::Unsolvable {
+                day: 19,
+                part: 2,
+                reason: "the looping rules 8 and 11 need rules 42 and 31"
+            })
+        );
+
+        let err = crate::day22::input_generator("Player 1:\n1\n2\n\nPlayer 2:\n3\n2").unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::new(7, 1, "a card that hasn't been dealt already")
+        );
+
+        let tile = format!("Tile 1:\n{}", ["#........."; 10].join("\n"));
+        let two = format!("{}\n\n{}", tile, tile.replace('1', "2"));
+        let err = crate::day20::input_generator(&two).unwrap_err();
+        assert_eq!(err, ParseError::new(23, 11, "a square number of tiles"));
+    }
+}
@@ -0,0 +1,342 @@
+/// A sample input from a puzzle description, with the answers it is documented to give.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Example {
+    pub day: u8,
+    pub input: &'static str,
+    pub part1: Option<&'static str>,
+    pub part2: Option<&'static str>,
+}
+
+impl Example {
+    /// The expected answer for `part`, as `Answer` displays it.
+    pub fn expected(&self, part: u8) -> Option<&'static str> {
+        match part {
+            1 => self.part1,
+            2 => self.part2,
+            _ => None,
+        }
+    }
+}
+
+/// Every example, grouped by day. Day 9's examples use a shorter preamble than the
+/// real puzzle, so the solver can't run them.
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        day: 1,
+        input: "1721\n979\n366\n299\n675\n1456",
+        part1: Some("514579"),
+        part2: Some("241861950"),
+    },
+    Example {
+        day: 2,
+        input: "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc",
+        part1: Some("2"),
+        part2: Some("1"),
+    },
+    Example {
+        day: 3,
+        input: "..##.......\n#...#...#..\n.#....#..#.\n..#.#...#.#\n.#...##..#.\n..#.##.....\n.#.#.#....#\n.#........#\n#.##...#...\n#...##....#\n.#..#...#.#",
+        part1: Some("7"),
+        part2: Some("336"),
+    },
+    Example {
+        day: 4,
+        input: "ecl:gry pid:860033327 eyr:2020 hcl:#fffffd\nbyr:1937 iyr:2017 cid:147 hgt:183cm\n\niyr:2013 ecl:amb cid:350 eyr:2023 pid:028048884\nhcl:#cfa07d byr:1929\n\nhcl:#ae17e1 iyr:2013\neyr:2024\necl:brn pid:760753108 byr:1931\nhgt:179cm\n\nhcl:#cfa07d eyr:2025 pid:166559648\niyr:2011 ecl:brn hgt:59in",
+        part1: Some("2"),
+        part2: Some("2"),
+    },
+    Example {
+        day: 4,
+        input: "eyr:1972 cid:100\nhcl:#18171d ecl:amb hgt:170 pid:186cm iyr:2018 byr:1926\n\niyr:2019\nhcl:#602927 eyr:1967 hgt:170cm\necl:grn pid:012533040 byr:1946\n\nhcl:dab227 iyr:2012\necl:brn hgt:182cm pid:021572410 eyr:2020 byr:1992 cid:277\n\nhgt:59cm ecl:zzz\neyr:2038 hcl:74454a iyr:2023\npid:3556412378 byr:2007",
+        part1: None,
+        part2: Some("0"),
+    },
+    Example {
+        day: 4,
+        input: "pid:087499704 hgt:74in ecl:grn iyr:2012 eyr:2030 byr:1980\nhcl:#623a2f\n\neyr:2029 ecl:blu cid:129 byr:1989\niyr:2014 pid:896056539 hcl:#a97842 hgt:165cm\n\nhcl:#888785\nhgt:164cm byr:2001 iyr:2015 cid:88\npid:545766238 ecl:hzl\neyr:2022\n\niyr:2010 hgt:158cm hcl:#b6652a ecl:blu byr:1944 eyr:2021 pid:093154719",
+        part1: None,
+        part2: Some("4"),
+    },
+    Example {
+        day: 5,
+        input: "FBFBBFFRLR\nBFFFBBFRRR\nFFFBBBFRRR\nBBFFBBFRLL",
+        part1: Some("820"),
+        part2: None,
+    },
+    Example {
+        day: 6,
+        input: "abc\n\na\nb\nc\n\nab\nac\n\na\na\na\na\n\nb",
+        part1: Some("11"),
+        part2: Some("6"),
+    },
+    Example {
+        day: 7,
+        input: "light red bags contain 1 bright white bag, 2 muted yellow bags.\ndark orange bags contain 3 bright white bags, 4 muted yellow bags.\nbright white bags contain 1 shiny gold bag.\nmuted yellow bags contain 2 shiny gold bags, 9 faded blue bags.\nshiny gold bags contain 1 dark olive bag, 2 vibrant plum bags.\ndark olive bags contain 3 faded blue bags, 4 dotted black bags.\nvibrant plum bags contain 5 faded blue bags, 6 dotted black bags.\nfaded blue bags contain no other bags.\ndotted black bags contain no other bags.",
+        part1: Some("4"),
+        part2: Some("32"),
+    },
+    Example {
+        day: 7,
+        input: "shiny gold bags contain 2 dark red bags.\ndark red bags contain 2 dark orange bags.\ndark orange bags contain 2 dark yellow bags.\ndark yellow bags contain 2 dark green bags.\ndark green bags contain 2 dark blue bags.\ndark blue bags contain 2 dark violet bags.\ndark violet bags contain no other bags.",
+        part1: None,
+        part2: Some("126"),
+    },
+    Example {
+        day: 8,
+        input: "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6",
+        part1: Some("5"),
+        part2: Some("index: 9, acc: 8"),
+    },
+    Example {
+        day: 10,
+        input: "16\n10\n15\n5\n1\n11\n7\n19\n6\n12\n4",
+        part1: Some("35"),
+        part2: Some("8"),
+    },
+    Example {
+        day: 10,
+        input: "28\n33\n18\n42\n31\n14\n46\n20\n48\n47\n24\n23\n49\n45\n19\n38\n39\n11\n1\n32\n25\n35\n8\n17\n7\n9\n4\n2\n34\n10\n3",
+        part1: Some("220"),
+        part2: Some("19208"),
+    },
+    Example {
+        day: 11,
+        input: "L.LL.LL.LL\nLLLLLLL.LL\nL.L.L..L..\nLLLL.LL.LL\nL.LL.LL.LL\nL.LLLLL.LL\n..L.L.....\nLLLLLLLLLL\nL.LLLLLL.L\nL.LLLLL.LL",
+        part1: Some("37"),
+        part2: Some("26"),
+    },
+    Example {
+        day: 12,
+        input: "F10\nN3\nF7\nR90\nF11",
+        part1: Some("25"),
+        part2: Some("286"),
+    },
+    Example {
+        day: 13,
+        input: "939\n7,13,x,x,59,x,31,19",
+        part1: Some("295"),
+        part2: Some("1068781"),
+    },
+    Example {
+        day: 13,
+        input: "939\n17,x,13,19",
+        part1: None,
+        part2: Some("3417"),
+    },
+    Example {
+        day: 13,
+        input: "939\n67,7,59,61",
+        part1: None,
+        part2: Some("754018"),
+    },
+    Example {
+        day: 13,
+        input: "939\n67,x,7,59,61",
+        part1: None,
+        part2: Some("779210"),
+    },
+    Example {
+        day: 13,
+        input: "939\n67,7,x,59,61",
+        part1: None,
+        part2: Some("1261476"),
+    },
+    Example {
+        day: 13,
+        input: "939\n1789,37,47,1889",
+        part1: None,
+        part2: Some("1202161486"),
+    },
+    Example {
+        day: 14,
+        input: "mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X\nmem[8] = 11\nmem[7] = 101\nmem[8] = 0",
+        part1: Some("165"),
+        part2: None,
+    },
+    Example {
+        day: 14,
+        input: "mask = 000000000000000000000000000000X1001X\nmem[42] = 100\nmask = 00000000000000000000000000000000X0XX\nmem[26] = 1",
+        part1: None,
+        part2: Some("208"),
+    },
+    Example {
+        day: 15,
+        input: "0,3,6",
+        part1: Some("436"),
+        part2: Some("175594"),
+    },
+    Example {
+        day: 15,
+        input: "1,3,2",
+        part1: Some("1"),
+        part2: None,
+    },
+    Example {
+        day: 15,
+        input: "2,1,3",
+        part1: Some("10"),
+        part2: None,
+    },
+    Example {
+        day: 15,
+        input: "1,2,3",
+        part1: Some("27"),
+        part2: None,
+    },
+    Example {
+        day: 15,
+        input: "2,3,1",
+        part1: Some("78"),
+        part2: None,
+    },
+    Example {
+        day: 15,
+        input: "3,2,1",
+        part1: Some("438"),
+        part2: None,
+    },
+    Example {
+        day: 15,
+        input: "3,1,2",
+        part1: Some("1836"),
+        part2: None,
+    },
+    Example {
+        day: 16,
+        input: "class: 1-3 or 5-7\nrow: 6-11 or 33-44\nseat: 13-40 or 45-50\n\nyour ticket:\n7,1,14\n\nnearby tickets:\n7,3,47\n40,4,50\n55,2,20\n38,6,12",
+        part1: Some("71"),
+        part2: Some("1"),
+    },
+    Example {
+        day: 17,
+        input: ".#.\n..#\n###",
+        part1: Some("112"),
+        part2: Some("848"),
+    },
+    Example {
+        day: 18,
+        input: "1 + 2 * 3 + 4 * 5 + 6",
+        part1: Some("71"),
+        part2: Some("231"),
+    },
+    Example {
+        day: 18,
+        input: "1 + (2 * 3) + (4 * (5 + 6))",
+        part1: Some("51"),
+        part2: Some("51"),
+    },
+    Example {
+        day: 18,
+        input: "2 * 3 + (4 * 5)",
+        part1: Some("26"),
+        part2: Some("46"),
+    },
+    Example {
+        day: 18,
+        input: "5 + (8 * 3 + 9 + 3 * 4 * 3)",
+        part1: Some("437"),
+        part2: Some("1445"),
+    },
+    Example {
+        day: 18,
+        input: "5 * 9 * (7 * 3 * 3 + 9 * 3 + (8 + 6 * 4))",
+        part1: Some("12240"),
+        part2: Some("669060"),
+    },
+    Example {
+        day: 18,
+        input: "((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2",
+        part1: Some("13632"),
+        part2: Some("23340"),
+    },
+    Example {
+        day: 19,
+        input: "0: 4 1 5\n1: 2 3 | 3 2\n2: 4 4 | 5 5\n3: 4 5 | 5 4\n4: \"a\"\n5: \"b\"\n\nababbb\nbababa\nabbbab\naaabbb\naaaabbb",
+        part1: Some("2"),
+        part2: None,
+    },
+    Example {
+        day: 19,
+        input: "42: 9 14 | 10 1\n9: 14 27 | 1 26\n10: 23 14 | 28 1\n1: \"a\"\n11: 42 31\n5: 1 14 | 15 1\n19: 14 1 | 14 14\n12: 24 14 | 19 1\n16: 15 1 | 14 14\n31: 14 17 | 1 13\n6: 14 14 | 1 14\n2: 1 24 | 14 4\n0: 8 11\n13: 14 3 | 1 12\n15: 1 | 14\n17: 14 2 | 1 7\n23: 25 1 | 22 14\n28: 16 1\n4: 1 1\n20: 14 14 | 1 15\n3: 5 14 | 16 1\n27: 1 6 | 14 18\n14: \"b\"\n21: 14 1 | 1 14\n25: 1 1 | 1 14\n22: 14 14\n8: 42\n26: 14 22 | 1 20\n18: 15 15\n7: 14 5 | 1 21\n24: 14 1\n\nabbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa\nbbabbbbaabaabba\nbabbbbaabbbbbabbbbbbaabaaabaaa\naaabbbbbbaaaabaababaabababbabaaabbababababaaa\nbbbbbbbaaaabbbbaaabbabaaa\nbbbababbbbaaaaaaaabbababaaababaabab\nababaaaaaabaaab\nababaaaaabbbaba\nbaabbaaaabbaaaababbaababb\nabbbbabbbbaaaababbbbbbaaaababb\naaaaabbaabaaaaababaa\naaaabbaaaabbaaa\naaaabbaabbaaaaaaabbbabbbaaabbaabaaa\nbabaaabbbaaabaababbaabababaaab\naabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba",
+        part1: Some("3"),
+        part2: Some("12"),
+    },
+    Example {
+        day: 20,
+        input: "Tile 2311:\n..##.#..#.\n##..#.....\n#...##..#.\n####.#...#\n##.##.###.\n##...#.###\n.#.#.#..##\n..#....#..\n###...#.#.\n..###..###\n\nTile 1951:\n#.##...##.\n#.####...#\n.....#..##\n#...######\n.##.#....#\n.###.#####\n###.##.##.\n.###....#.\n..#.#..#.#\n#...##.#..\n\nTile 1171:\n####...##.\n#..##.#..#\n##.#..#.#.\n.###.####.\n..###.####\n.##....##.\n.#...####.\n#.##.####.\n####..#...\n.....##...\n\nTile 1427:\n###.##.#..\n.#..#.##..\n.#.##.#..#\n#.#.#.##.#\n....#...##\n...##..##.\n...#.#####\n.#.####.#.\n..#..###.#\n..##.#..#.\n\nTile 1489:\n##.#.#....\n..##...#..\n.##..##...\n..#...#...\n#####...#.\n#..#.#.#.#\n...#.#.#..\n##.#...##.\n..##.##.##\n###.##.#..\n\nTile 2473:\n#....####.\n#..#.##...\n#.##..#...\n######.#.#\n.#...#.#.#\n.#########\n.###.#..#.\n########.#\n##...##.#.\n..###.#.#.\n\nTile 2971:\n..#.#....#\n#...###...\n#.#.###...\n##.##..#..\n.#####..##\n.#..####.#\n#..#.#..#.\n..####.###\n..#.#.###.\n...#.#.#.#\n\nTile 2729:\n...#.#.#.#\n####.#....\n..#.#.....\n....#..#.#\n.##..##.#.\n.#.####...\n####.#.#..\n##.####...\n##..#.##..\n#.##...##.\n\nTile 3079:\n#.#.#####.\n.#..######\n..#.......\n######....\n####.#..#.\n.#...#.##.\n#.#####.##\n..#.###...\n..#.......\n..#.###...",
+        part1: Some("20899048083289"),
+        part2: Some("273"),
+    },
+    Example {
+        day: 21,
+        input: "mxmxvkd kfcds sqjhc nhms (contains dairy, fish)\ntrh fvjkl sbzzf mxmxvkd (contains dairy)\nsqjhc fvjkl (contains soy)\nsqjhc mxmxvkd sbzzf (contains fish)",
+        part1: Some("5"),
+        part2: Some("mxmxvkd,sqjhc,fvjkl"),
+    },
+    Example {
+        day: 22,
+        input: "Player 1:\n9\n2\n6\n3\n1\n\nPlayer 2:\n5\n8\n4\n7\n10",
+        part1: Some("306"),
+        part2: Some("291"),
+    },
+    Example {
+        day: 23,
+        input: "389125467",
+        part1: Some("67384529"),
+        part2: Some("149245887792"),
+    },
+    Example {
+        day: 24,
+        input: "sesenwnenenewseeswwswswwnenewsewsw\nneeenesenwnwwswnenewnwwsewnenwseswesw\nseswneswswsenwwnwse\nnwnwneseeswswnenewneswwnewseswneseene\nswweswneswnenwsewnwneneseenw\neesenwseswswnenwswnwnwsewwnwsene\nsewnenenenesenwsewnenwwwse\nwenwwweseeeweswwwnwwe\nwsweesenenewnwwnwsenewsenwwsesesenwne\nneeswseenwwswnwswswnw\nnenwswwsewswnenenewsenwsenwnesesenew\nenewnwewneswsewnwswenweswnenwsenwsw\nsweneswneswneneenwnewenewwneswswnese\nswwesenesewenwneswnwwneseswwne\nenesenwswwswneneswsenwnewswseenwsese\nwnwnesenesenenwwnenwsewesewsesesew\nnenewswnwewswnenesenwnesewesw\neneswnwswnwsenenwnwnwwseeswneewsenese\nneswnwewnwnwseenwseesewsenwsweewe\nwseweeenwnesenwwwswnew",
+        part1: Some("10"),
+        part2: Some("2208"),
+    },
+    Example {
+        day: 25,
+        input: "5764801\n17807724",
+        part1: Some("14897079"),
+        part2: None,
+    },
+];
+
+/// The examples for `day`, in catalog order.
+pub fn examples(day: u8) -> impl Iterator<Item = &'static Example> {
+    EXAMPLES.iter().filter(move |example| example.day == day)
+}
+
+/// The `n`th example for `day`, counting from 1.
+pub fn example(day: u8, n: usize) -> Option<&'static Example> {
+    examples(day).nth(n.checked_sub(1)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::solve;
+
+    #[test]
+    fn test_examples() {
+        for example in EXAMPLES {
+            for part in 1..=2 {
+                if let Some(expected) = example.expected(part) {
+                    let answer = solve(example.day, part, example.input).unwrap();
+                    assert_eq!(
+                        answer.to_string(),
+                        expected,
+                        "day {} part {} on {:?}",
+                        example.day,
+                        part,
+                        example.input
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(example(13, 2).unwrap().part2, Some("3417"));
+        assert!(example(13, 0).is_none());
+        assert!(example(9, 1).is_none());
+    }
+}
@@ -0,0 +1,163 @@
+//! The reasoning behind an answer, for reviewing a solution or teaching how it was found:
+//! the intermediate results a solver settled on before producing its number.
+
+use std::fmt::Display;
+
+use crate::{stream, Answer, Error, Puzzle};
+
+/// One stage of a derivation, with what it established as `key: value` facts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub title: String,
+    pub facts: Vec<(String, String)>,
+}
+
+impl Step {
+    pub fn new(title: impl Into<String>) -> Self {
+        Step {
+            title: title.into(),
+            facts: vec![],
+        }
+    }
+
+    pub fn fact(mut self, key: impl Display, value: impl Display) -> Self {
+        self.facts.push((key.to_string(), value.to_string()));
+        self
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.title)?;
+        for (key, value) in &self.facts {
+            write!(f, "\n   {}: {}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    pub day: u8,
+    pub part: u8,
+    pub steps: Vec<Step>,
+    pub answer: Answer,
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "day {} part {}", self.day, self.part)?;
+        for (i, step) in self.steps.iter().enumerate() {
+            writeln!(f, "{}. {}", i + 1, step)?;
+        }
+        write!(f, "answer: {}", self.answer)
+    }
+}
+
+fn explain_puzzle<P: Puzzle>(part: u8, input: &str) -> Result<Explanation, Error> {
+    if !P::has_part(part) {
+        return Err(Error::UnknownPart { day: P::DAY, part });
+    }
+
+    let input = P::parse(input)?;
+    let steps = P::explain(&input, part).ok_or(Error::NoExplanation { day: P::DAY, part })?;
+    Ok(Explanation {
+        day: P::DAY,
+        part,
+        steps,
+        answer: P::solve_part(&input, part)?,
+    })
+}
+
+/// Solves `part` of `day`, along with the steps that led to the answer.
+pub fn explain(day: u8, part: u8, input: &str) -> Result<Explanation, Error> {
+    let input = &stream::normalize(input);
+    with_puzzle!(day, P => explain_puzzle::<P>(part, input), _ => Err(Error::UnknownDay(day)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn facts(explanation: &Explanation, step: usize) -> Vec<(&str, &str)> {
+        explanation.steps[step]
+            .facts
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_explain() {
+        let tickets = "class: 0-1 or 4-19\nrow: 0-5 or 8-19\nseat: 0-13 or 16-19\n\nyour ticket:\n11,12,13\n\nnearby tickets:\n3,9,18\n15,1,5\n5,14,9";
+        let explanation = explain(16, 2, tickets).unwrap();
+        assert_eq!(
+            facts(&explanation, 2),
+            vec![
+                ("row", "column 0, 11 on your ticket"),
+                ("class", "column 1, 12 on your ticket"),
+                ("seat", "column 2, 13 on your ticket")
+            ]
+        );
+
+        let foods = "mxmxvkd kfcds sqjhc nhms (contains dairy, fish)\ntrh fvjkl sbzzf mxmxvkd (contains dairy)\nsqjhc fvjkl (contains soy)\nsqjhc mxmxvkd sbzzf (contains fish)";
+        let explanation = explain(21, 2, foods).unwrap();
+        assert_eq!(
+            facts(&explanation, 1),
+            vec![("dairy", "mxmxvkd"), ("fish", "sqjhc"), ("soy", "fvjkl")]
+        );
+
+        let bags = crate::examples::example(7, 1).unwrap().input;
+        let explanation = explain(7, 1, bags).unwrap();
+        assert_eq!(explanation.answer, 4);
+        assert_eq!(
+            facts(&explanation, 0),
+            vec![
+                ("bright white", "shiny gold"),
+                ("muted yellow", "shiny gold"),
+                ("dark orange", "bright white > shiny gold"),
+                ("light red", "bright white > shiny gold")
+            ]
+        );
+
+        let program = "nop +0\nacc +1\njmp +4\nacc +3\njmp -3\nacc -99\nacc +1\njmp -4\nacc +6";
+        let explanation = explain(8, 2, program).unwrap();
+        assert_eq!(
+            facts(&explanation, 0),
+            vec![("instruction 7", "jmp -4 became nop -4")]
+        );
+
+        let explanation = explain(13, 2, "939\n7,13,x,x,59,x,31,19").unwrap();
+        assert_eq!(explanation.answer, 1068781);
+        assert_eq!(
+            facts(&explanation, 0)[..2],
+            [("bus 7", "t = 0 (mod 7)"), ("bus 13", "t = 12 (mod 13)")]
+        );
+
+        assert_eq!(
+            explain(1, 1, "1721\n299").map(|explanation| explanation.answer),
+            Err(Error::NoExplanation { day: 1, part: 1 })
+        );
+        assert_eq!(
+            explain(25, 2, "1\n2").map(|explanation| explanation.answer),
+            Err(Error::UnknownPart { day: 25, part: 2 })
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let explanation = Explanation {
+            day: 8,
+            part: 2,
+            steps: vec![
+                Step::new("flipped one instruction").fact("instruction 7", "jmp -4 became nop -4"),
+                Step::new("ran off the end"),
+            ],
+            answer: Answer::structured(vec![("index", 9), ("acc", 8)]),
+        };
+        assert_eq!(
+            explanation.to_string(),
+            "day 8 part 2\n1. flipped one instruction\n   instruction 7: jmp -4 became nop -4\n2. ran off the end\nanswer: index: 9, acc: 8"
+        );
+    }
+}
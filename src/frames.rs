@@ -0,0 +1,216 @@
+use std::{collections::HashSet, fmt::Write, str::FromStr};
+
+use crate::automaton::{Automaton, Rule, Topology};
+use crate::grid::Grid;
+use crate::{day11, day17, day24, stream, Error};
+
+/// One image of a generation. Each cell is a level indexing into `palette`; 0 is the
+/// background and the last level is a live cell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub name: String,
+    pub generation: usize,
+    pub image: Grid<u8>,
+    pub palette: &'static [char],
+}
+
+impl Frame {
+    fn max(&self) -> u8 {
+        (self.palette.len() - 1) as u8
+    }
+
+    /// How many cells are at the top level.
+    pub fn live(&self) -> usize {
+        self.image
+            .iter()
+            .filter(|level| **level == self.max())
+            .count()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Plain PBM: live cells are black, everything else white.
+    Pbm,
+    /// Plain PGM: darker for higher levels, so live cells are black.
+    Pgm,
+    Ascii,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pbm" => Ok(Format::Pbm),
+            "pgm" => Ok(Format::Pgm),
+            "ascii" => Ok(Format::Ascii),
+            _ => Err(s.into()),
+        }
+    }
+}
+
+/// Writes `values` as whitespace-separated rows, wrapped to the 70 columns plain netpbm
+/// files are meant to stay within.
+fn netpbm_rows(out: &mut String, image: &Grid<u8>, sep: &str, value: impl Fn(u8) -> String) {
+    for row in image.rows() {
+        let mut width = 0;
+        for (i, level) in row.iter().enumerate() {
+            let value = value(*level);
+            if i > 0 && width + sep.len() + value.len() > 70 {
+                out.push('\n');
+                width = 0;
+            } else if i > 0 {
+                out.push_str(sep);
+                width += sep.len();
+            }
+            out.push_str(&value);
+            width += value.len();
+        }
+        out.push('\n');
+    }
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Pbm => "pbm",
+            Format::Pgm => "pgm",
+            Format::Ascii => "txt",
+        }
+    }
+
+    pub fn render(self, frame: &Frame) -> String {
+        let image = &frame.image;
+        let max = frame.max();
+        let mut out = String::new();
+
+        match self {
+            Format::Pbm => {
+                writeln!(out, "P1\n{} {}", image.width(), image.height()).unwrap();
+                netpbm_rows(&mut out, image, "", |level| {
+                    if level == max { "1" } else { "0" }.to_owned()
+                });
+            }
+            Format::Pgm => {
+                writeln!(out, "P2\n{} {}\n{}", image.width(), image.height(), max).unwrap();
+                netpbm_rows(&mut out, image, " ", |level| (max - level).to_string());
+            }
+            Format::Ascii => {
+                writeln!(out, "{}", image.map(|level| frame.palette[*level as usize])).unwrap();
+            }
+        }
+
+        out
+    }
+}
+
+/// The live cells of every generation, starting with the initial one. Stops early once
+/// the automaton settles.
+pub fn history<T: Topology, R: Rule>(
+    mut automaton: Automaton<T, R>,
+    generations: usize,
+) -> Vec<HashSet<T::Cell>> {
+    let mut out = vec![automaton.live().clone()];
+    while out.len() <= generations && automaton.step() {
+        out.push(automaton.live().clone());
+    }
+    out
+}
+
+/// Renders the generations of `day`'s automaton. Without `generations`, day 11 runs until
+/// the seats settle and days 17 and 24 run as long as the puzzle does; day 24 part 1 is
+/// just the initial floor.
+pub fn frames(
+    day: u8,
+    part: u8,
+    input: &str,
+    generations: Option<usize>,
+) -> Result<Vec<Frame>, Error> {
+    let input = &stream::normalize(input);
+    match (day, part) {
+        (11, 1) | (11, 2) => {
+            let layout = day11::input_generator(input)?;
+            Ok(day11::frames(
+                &layout,
+                part,
+                generations.unwrap_or(usize::MAX),
+            ))
+        }
+        (17, 1) | (17, 2) => {
+            let cubes = day17::input_generator(input)?;
+            Ok(day17::frames(&cubes, part, generations.unwrap_or(6)))
+        }
+        (24, 1) | (24, 2) => {
+            let paths = day24::input_generator(input)?;
+            let generations = if part == 1 {
+                0
+            } else {
+                generations.unwrap_or(100)
+            };
+            Ok(day24::frames(&paths, generations))
+        }
+        (11, _) | (17, _) | (24, _) => Err(Error::UnknownPart { day, part }),
+        _ => Err(Error::NoFrames(day)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let frame = Frame {
+            name: "gen000".to_owned(),
+            generation: 0,
+            image: Grid::parse(".L#\n#..", |c| ".L#".find(c).map(|i| i as u8), "").unwrap(),
+            palette: &['.', 'L', '#'],
+        };
+
+        assert_eq!(Format::Ascii.render(&frame), ".L#\n#..\n");
+        assert_eq!(Format::Pbm.render(&frame), "P1\n3 2\n001\n100\n");
+        assert_eq!(Format::Pgm.render(&frame), "P2\n3 2\n2\n2 1 0\n0 2 2\n");
+
+        let wide = Frame {
+            image: Grid::new(1, 40, 0),
+            ..frame
+        };
+        let pgm = Format::Pgm.render(&wide);
+        assert!(pgm.lines().all(|line| line.len() <= 70));
+        assert_eq!(pgm.split_whitespace().count(), 4 + 40);
+    }
+
+    #[test]
+    fn test_frames() {
+        let seats = frames(11, 1, "L.L\nLLL\nL.L\n", None).unwrap();
+        assert_eq!(Format::Ascii.render(&seats[0]), "L.L\nLLL\nL.L\n");
+        assert_eq!(Format::Ascii.render(&seats[1]), "#.#\n###\n#.#\n");
+
+        // the bounds cover every generation, so generation 0 also gets the z = ±1 slices
+        let cubes = frames(17, 1, ".#.\n..#\n###", Some(1)).unwrap();
+        let names: Vec<_> = cubes.iter().map(|frame| frame.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "gen000-z-1",
+                "gen000-z0",
+                "gen000-z1",
+                "gen001-z-1",
+                "gen001-z0",
+                "gen001-z1"
+            ]
+        );
+        assert_eq!(Format::Ascii.render(&cubes[1]), ".#.\n..#\n###\n...\n");
+
+        let floor = frames(24, 1, "esew\nnwwswee", None).unwrap();
+        assert_eq!(floor.len(), 1);
+        assert_eq!(Format::Ascii.render(&floor[0]), "# \n #\n");
+
+        assert_eq!(frames(1, 1, "", None), Err(Error::NoFrames(1)));
+        assert_eq!(
+            frames(17, 3, "", None),
+            Err(Error::UnknownPart { day: 17, part: 3 })
+        );
+    }
+}
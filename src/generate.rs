@@ -0,0 +1,1253 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt::Write,
+};
+
+use crate::{
+    automaton::{Automaton, SquareGrid},
+    grid::Grid,
+    number_theory, Error, Rng,
+};
+
+/// Produces a valid, solvable puzzle input for `day`. The same seed and size always
+/// give the same input. `size` is clamped to what the input format and the solvers can
+/// handle and means:
+///
+/// | day | size                        | at most                              |
+/// |-----|-----------------------------|--------------------------------------|
+/// | 1   | expense entries             | 500                                  |
+/// | 2   | passwords                   |                                      |
+/// | 3   | rows of the slope           |                                      |
+/// | 4   | passports                   |                                      |
+/// | 5   | boarding passes             | 1000                                 |
+/// | 6   | groups                      |                                      |
+/// | 7   | bag colours                 | 900                                  |
+/// | 8   | instructions                | 2000                                 |
+/// | 9   | numbers, including preamble | 1000                                 |
+/// | 10  | adapters                    | 100, and none rated above 255 jolts  |
+/// | 11  | side of the seat layout     | 100                                  |
+/// | 12  | navigation instructions     |                                      |
+/// | 13  | buses                       | 9, all multiplying to under 10^18    |
+/// | 14  | mask blocks                 |                                      |
+/// | 15  | starting numbers            | 10                                   |
+/// | 16  | ticket fields               | 20, one for each field name          |
+/// | 17  | side of the initial slice   | 12                                   |
+/// | 18  | expressions                 |                                      |
+/// | 19  | messages                    |                                      |
+/// | 20  | tiles along each side       | 12                                   |
+/// | 21  | foods                       | 200                                  |
+/// | 22  | cards per player            | 30                                   |
+/// | 23  | ignored; always nine cups   |                                      |
+/// | 24  | tile paths                  |                                      |
+/// | 25  | largest loop size           |                                      |
+pub fn generate(day: u8, seed: u64, size: usize) -> Result<String, Error> {
+    let rng = &mut Rng::new(seed);
+    let input = match day {
+        1 => day1(rng, size.clamp(5, 500)),
+        2 => day2(rng, size.max(1)),
+        3 => day3(rng, size.max(1)),
+        4 => day4(rng, size.max(1)),
+        5 => day5(rng, size.clamp(3, 1000)),
+        6 => day6(rng, size.max(1)),
+        7 => day7(rng, size.clamp(2, 900)),
+        8 => day8(rng, size.clamp(4, 2000)),
+        9 => day9(rng, size.clamp(30, 1000)),
+        10 => day10(rng, size.clamp(1, 100)),
+        11 => day11(rng, size.clamp(1, 100)),
+        12 => day12(rng, size.max(1)),
+        13 => day13(rng, size.clamp(1, 9)),
+        14 => day14(rng, size.max(1)),
+        15 => day15(rng, size.clamp(2, 10)),
+        16 => day16(rng, size.clamp(1, 20)),
+        17 => day17(rng, size.clamp(1, 12)),
+        18 => day18(rng, size.max(1)),
+        19 => day19(rng, size.max(1)),
+        20 => day20(rng, size.clamp(3, 12)),
+        21 => day21(rng, size.clamp(1, 200)),
+        22 => day22(rng, size.clamp(1, 30)),
+        23 => day23(rng),
+        24 => day24(rng, size.max(1)),
+        25 => day25(rng, size.max(2)),
+        _ => return Err(Error::UnknownDay(day)),
+    };
+
+    Ok(input)
+}
+
+fn lines<T: ToString>(items: impl IntoIterator<Item = T>) -> String {
+    items
+        .into_iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn letter(rng: &mut Rng) -> char {
+    (b'a' + rng.below(26) as u8) as char
+}
+
+fn word(rng: &mut Rng, len: usize) -> String {
+    (0..len).map(|_| letter(rng)).collect()
+}
+
+/// Exactly one pair and one triple sum to 2020; the rest are too large to take part.
+fn day1(rng: &mut Rng, size: usize) -> String {
+    let planted = loop {
+        let a = rng.between(1, 1009);
+        let b = rng.between(1, 1000);
+        let c = rng.between(1, 1000);
+        let planted = vec![a, 2020 - a, b, c, 2020 - b - c];
+
+        let pairs = (0..5)
+            .flat_map(|i| (i + 1..5).map(move |j| (i, j)))
+            .filter(|&(i, j)| planted[i] + planted[j] == 2020)
+            .count();
+        let triples = (0..5)
+            .flat_map(|i| (i + 1..5).flat_map(move |j| (j + 1..5).map(move |k| (i, j, k))))
+            .filter(|&(i, j, k)| planted[i] + planted[j] + planted[k] == 2020)
+            .count();
+        let distinct = planted.iter().collect::<HashSet<_>>().len() == 5;
+
+        if pairs == 1 && triples == 1 && distinct {
+            break planted;
+        }
+    };
+
+    let mut numbers = planted.clone();
+    while numbers.len() < size {
+        let filler = rng.between(1011, 2019);
+        let clashes = numbers.contains(&filler)
+            || planted.iter().any(|x| filler + x == 2020)
+            || planted
+                .iter()
+                .enumerate()
+                .any(|(i, x)| planted[i + 1..].iter().any(|y| filler + x + y == 2020));
+        if !clashes {
+            numbers.push(filler);
+        }
+    }
+
+    rng.shuffle(&mut numbers);
+    lines(numbers)
+}
+
+fn day2(rng: &mut Rng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        let low = rng.between(1, 10);
+        let high = rng.between(low, low + 10);
+        let c = letter(rng);
+        let len = rng.between(high, high + 8);
+        let password: String = (0..len)
+            .map(|_| if rng.chance(0.4) { c } else { letter(rng) })
+            .collect();
+        format!("{}-{} {}: {}", low, high, c, password)
+    }))
+}
+
+fn day3(rng: &mut Rng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        (0..31)
+            .map(|_| if rng.chance(0.25) { '#' } else { '.' })
+            .collect::<String>()
+    }))
+}
+
+fn day4(rng: &mut Rng, size: usize) -> String {
+    const COLOURS: [&str; 7] = ["amb", "blu", "brn", "gry", "grn", "hzl", "oth"];
+
+    let passports = (0..size).map(|_| {
+        let mut fields = vec![];
+        for &field in &["byr", "iyr", "eyr", "hgt", "hcl", "ecl", "pid", "cid"] {
+            let present = if field == "cid" { 0.5 } else { 0.9 };
+            if !rng.chance(present) {
+                continue;
+            }
+
+            let valid = rng.chance(0.8);
+            let year = |rng: &mut Rng, low: u64, high: u64| {
+                if valid {
+                    rng.between(low, high)
+                } else if rng.chance(0.5) {
+                    rng.between(low - 20, low - 1)
+                } else {
+                    rng.between(high + 1, high + 20)
+                }
+                .to_string()
+            };
+
+            let value = match field {
+                "byr" => year(rng, 1920, 2002),
+                "iyr" => year(rng, 2010, 2020),
+                "eyr" => year(rng, 2020, 2030),
+                "hgt" => match (valid, rng.chance(0.5)) {
+                    (true, true) => format!("{}cm", rng.between(150, 193)),
+                    (true, false) => format!("{}in", rng.between(59, 76)),
+                    (false, true) => format!("{}cm", rng.between(100, 149)),
+                    (false, false) => rng.between(59, 193).to_string(),
+                },
+                "hcl" if valid => format!("#{:06x}", rng.below(1 << 24)),
+                "hcl" => format!("{:06x}", rng.below(1 << 24)),
+                "ecl" if valid => rng.choose(&COLOURS).to_string(),
+                "ecl" => word(rng, 3),
+                "pid" if valid => format!("{:09}", rng.below(1_000_000_000)),
+                "pid" => format!("{:010}", rng.below(10_000_000_000)),
+                _ => rng.between(100, 350).to_string(),
+            };
+            fields.push(format!("{}:{}", field, value));
+        }
+
+        if fields.is_empty() {
+            fields.push(format!("cid:{}", rng.between(100, 350)));
+        }
+        rng.shuffle(&mut fields);
+
+        let mut passport = String::new();
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                passport.push(if rng.chance(0.3) { '\n' } else { ' ' });
+            }
+            passport.push_str(field);
+        }
+        passport
+    });
+
+    passports.collect::<Vec<_>>().join("\n\n")
+}
+
+/// A contiguous run of seats with one missing from the middle.
+fn day5(rng: &mut Rng, size: usize) -> String {
+    let first = rng.between(8, 1015 - size as u64);
+    let missing = rng.between(first + 1, first + size as u64 - 1);
+    let mut seats: Vec<_> = (first..=first + size as u64)
+        .filter(|&seat| seat != missing)
+        .collect();
+    rng.shuffle(&mut seats);
+
+    lines(seats.into_iter().map(|seat| {
+        (0..10)
+            .rev()
+            .map(|bit| match (bit >= 3, seat >> bit & 1) {
+                (true, 0) => 'F',
+                (true, _) => 'B',
+                (false, 0) => 'L',
+                (false, _) => 'R',
+            })
+            .collect::<String>()
+    }))
+}
+
+fn day6(rng: &mut Rng, size: usize) -> String {
+    let groups = (0..size).map(|_| {
+        let common: HashSet<char> = (0..rng.below(4)).map(|_| letter(rng)).collect();
+        let people = (0..rng.between(1, 5)).map(|_| {
+            let mut answers = common.clone();
+            for _ in 0..rng.below(6) {
+                answers.insert(letter(rng));
+            }
+            if answers.is_empty() {
+                answers.insert(letter(rng));
+            }
+
+            let mut answers: Vec<_> = answers.into_iter().collect();
+            answers.sort_unstable();
+            rng.shuffle(&mut answers);
+            answers.into_iter().collect::<String>()
+        });
+        lines(people)
+    });
+
+    groups.collect::<Vec<_>>().join("\n\n")
+}
+
+/// Bags sit on one of six levels and only contain bags from deeper levels, so the rules
+/// form a DAG with a bounded total count. Shiny gold is kept in the middle.
+fn day7(rng: &mut Rng, size: usize) -> String {
+    const ADJECTIVES: [&str; 30] = [
+        "bright", "clear", "dark", "dim", "dotted", "drab", "dull", "faded", "light", "mirrored",
+        "muted", "pale", "plaid", "posh", "shiny", "striped", "vibrant", "wavy", "dusty", "bold",
+        "vivid", "faint", "mute", "dingy", "pastel", "deep", "soft", "neon", "matte", "glossy",
+    ];
+    const COLOURS: [&str; 30] = [
+        "aqua",
+        "beige",
+        "black",
+        "blue",
+        "bronze",
+        "brown",
+        "chartreuse",
+        "coral",
+        "crimson",
+        "cyan",
+        "fuchsia",
+        "gold",
+        "gray",
+        "green",
+        "indigo",
+        "lavender",
+        "lime",
+        "magenta",
+        "maroon",
+        "olive",
+        "orange",
+        "plum",
+        "purple",
+        "red",
+        "salmon",
+        "silver",
+        "tan",
+        "teal",
+        "tomato",
+        "violet",
+    ];
+    const LEVELS: u64 = 6;
+
+    let mut names: Vec<String> = ADJECTIVES
+        .iter()
+        .flat_map(|a| COLOURS.iter().map(move |c| format!("{} {}", a, c)))
+        .filter(|name| name != "shiny gold")
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    names.sort_unstable();
+    rng.shuffle(&mut names);
+    names.truncate(size - 1);
+    names.push("shiny gold".to_owned());
+
+    let levels: Vec<u64> = names
+        .iter()
+        .map(|name| {
+            if name == "shiny gold" {
+                LEVELS / 2
+            } else {
+                rng.below(LEVELS)
+            }
+        })
+        .collect();
+
+    let mut rules: Vec<String> = names
+        .iter()
+        .zip(&levels)
+        .map(|(name, &level)| {
+            let mut deeper: Vec<_> = names
+                .iter()
+                .zip(&levels)
+                .filter(|(_, &other)| other > level)
+                .map(|(other, _)| other)
+                .collect();
+            rng.shuffle(&mut deeper);
+            deeper.truncate(rng.below(5) as usize);
+
+            if deeper.is_empty() {
+                format!("{} bags contain no other bags.", name)
+            } else {
+                let contents: Vec<_> = deeper
+                    .iter()
+                    .map(|inner| match rng.between(1, 9) {
+                        1 => format!("1 {} bag", inner),
+                        n => format!("{} {} bags", n, inner),
+                    })
+                    .collect();
+                format!("{} bags contain {}.", name, contents.join(", "))
+            }
+        })
+        .collect();
+
+    rng.shuffle(&mut rules);
+    lines(rules)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Acc,
+    Jmp,
+    Nop,
+}
+
+/// Runs a day 8 program, returning whether it ran off the end and which instructions
+/// it executed.
+fn run_program(program: &[(Op, i64)]) -> (bool, Vec<usize>) {
+    let mut seen = vec![false; program.len()];
+    let mut visited = vec![];
+    let mut pc = 0i64;
+
+    while (0..program.len() as i64).contains(&pc) && !seen[pc as usize] {
+        seen[pc as usize] = true;
+        visited.push(pc as usize);
+        pc += match program[pc as usize] {
+            (Op::Jmp, offset) => offset,
+            _ => 1,
+        };
+    }
+
+    (pc == program.len() as i64, visited)
+}
+
+fn flip(op: Op) -> Op {
+    match op {
+        Op::Jmp => Op::Nop,
+        Op::Nop => Op::Jmp,
+        Op::Acc => Op::Acc,
+    }
+}
+
+/// Builds a terminating program along a random path through the instructions, then
+/// flips one jmp/nop on that path, keeping the result only if that flip is the single
+/// one that repairs it.
+fn day8(rng: &mut Rng, size: usize) -> String {
+    let n = size as i64;
+    let program = loop {
+        let mut rest: Vec<i64> = (1..n).collect();
+        rng.shuffle(&mut rest);
+        rest.truncate(rng.between(size as u64 / 2, size as u64 - 1) as usize);
+        let path: Vec<i64> = std::iter::once(0).chain(rest).collect();
+
+        let mut program: Vec<(Op, i64)> = (0..n)
+            .map(|i| match rng.below(3) {
+                0 => (Op::Acc, rng.between(0, 100) as i64 - 50),
+                1 => (Op::Nop, rng.below(n as u64) as i64 - i),
+                _ => (Op::Jmp, rng.below(n as u64) as i64 - i),
+            })
+            .collect();
+
+        for (i, &at) in path.iter().enumerate() {
+            let next = path.get(i + 1).copied().unwrap_or(n);
+            program[at as usize] = if next == at + 1 && rng.chance(0.6) {
+                if rng.chance(0.7) {
+                    (Op::Acc, rng.between(0, 100) as i64 - 50)
+                } else {
+                    (Op::Nop, rng.below(n as u64) as i64 - at)
+                }
+            } else {
+                (Op::Jmp, next - at)
+            };
+        }
+
+        let flippable: Vec<_> = path
+            .iter()
+            .map(|&at| at as usize)
+            .filter(|&at| program[at].0 != Op::Acc)
+            .collect();
+        if flippable.is_empty() {
+            continue;
+        }
+        let corrupt = *rng.choose(&flippable);
+        program[corrupt].0 = flip(program[corrupt].0);
+
+        let (terminates, visited) = run_program(&program);
+        if terminates {
+            continue;
+        }
+
+        let fixes: Vec<_> = visited
+            .into_iter()
+            .filter(|&at| program[at].0 != Op::Acc)
+            .filter(|&at| {
+                let mut fixed = program.clone();
+                fixed[at].0 = flip(fixed[at].0);
+                run_program(&fixed).0
+            })
+            .collect();
+        if fixes == [corrupt] {
+            break program;
+        }
+    };
+
+    lines(program.into_iter().map(|(op, offset)| {
+        let op = match op {
+            Op::Acc => "acc",
+            Op::Jmp => "jmp",
+            Op::Nop => "nop",
+        };
+        format!("{} {:+}", op, offset)
+    }))
+}
+
+fn is_sum_of_two(window: &[u64], target: u64) -> bool {
+    let values: HashSet<_> = window.iter().collect();
+    values
+        .iter()
+        .any(|&&a| a * 2 != target && target > a && values.contains(&(target - a)))
+}
+
+/// Each number is the sum of two of the smaller values in the previous 25, except for
+/// one, which is the sum of an earlier contiguous run instead.
+fn day9(rng: &mut Rng, size: usize) -> String {
+    let mut numbers: Vec<u64> = vec![];
+    while numbers.len() < 25 {
+        let n = rng.between(1, 50);
+        if !numbers.contains(&n) {
+            numbers.push(n);
+        }
+    }
+
+    let invalid_at = rng.between(size.max(50) as u64 / 2, size as u64 - 1) as usize;
+    while numbers.len() < size {
+        let window = &numbers[numbers.len() - 25..];
+
+        if numbers.len() == invalid_at {
+            let len = rng.between(2, 5) as usize;
+            let start = rng.between(0, (numbers.len() - len) as u64) as usize;
+            let target = numbers[start..start + len].iter().sum();
+            if !is_sum_of_two(window, target) && !numbers.contains(&target) {
+                numbers.push(target);
+            }
+            continue;
+        }
+
+        let mut smallest = window.to_vec();
+        smallest.sort_unstable();
+        smallest.dedup();
+        smallest.truncate(8);
+        let a = *rng.choose(&smallest);
+        let b = *rng.choose(&smallest);
+        if a != b {
+            numbers.push(a + b);
+        }
+    }
+
+    lines(numbers)
+}
+
+/// Joltages climb in steps of 1 or 3, with at most four 1-steps in a row.
+fn day10(rng: &mut Rng, size: usize) -> String {
+    let mut adapters = vec![1u64];
+    let mut run = 1;
+    while adapters.len() < size {
+        let step = if run < 4 && rng.chance(0.7) { 1 } else { 3 };
+        run = if step == 1 { run + 1 } else { 0 };
+
+        let next = adapters[adapters.len() - 1] + step;
+        if next > 255 {
+            break;
+        }
+        adapters.push(next);
+    }
+
+    rng.shuffle(&mut adapters);
+    lines(adapters)
+}
+
+/// Whether the seats stop changing under both parts' rules within `limit` rounds. Gives up
+/// early on a layout that flips back to where it was two rounds before.
+fn seats_settle(seats: &Grid<bool>, limit: usize) -> bool {
+    let rules = vec![
+        (SquareGrid::adjacent(seats), 4),
+        (SquareGrid::line_of_sight(seats), 5),
+    ];
+    rules.into_iter().all(|(topology, crowd)| {
+        let rule =
+            move |filled: bool, nearby: usize| if filled { nearby < crowd } else { nearby == 0 };
+        let mut seating = Automaton::new(topology, rule, vec![]);
+        let mut before = seating.live().clone();
+        for _ in 0..limit {
+            let previous = seating.live().clone();
+            if !seating.step() {
+                return true;
+            }
+            if *seating.live() == before {
+                return false;
+            }
+            before = previous;
+        }
+        false
+    })
+}
+
+/// Random layouts are redrawn until the seats settle under both parts' rules, since some
+/// of them flip between two states forever.
+fn day11(rng: &mut Rng, size: usize) -> String {
+    let seats = loop {
+        let seats = Grid::from_fn(size, size, |_| rng.chance(0.75));
+        if seats_settle(&seats, 10 * size + 100) {
+            break seats;
+        }
+    };
+
+    lines(seats.rows().map(|row| {
+        row.iter()
+            .map(|&seat| if seat { 'L' } else { '.' })
+            .collect::<String>()
+    }))
+}
+
+fn day12(rng: &mut Rng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        let action = *rng.choose(&['N', 'S', 'E', 'W', 'L', 'R', 'F', 'F']);
+        let value = match action {
+            'L' | 'R' => *rng.choose(&[90, 180, 270]),
+            _ => rng.between(1, 100),
+        };
+        format!("{}{}", action, value)
+    }))
+}
+
+/// Distinct primes keep the bus ids coprime, and their product stays well inside u64.
+fn day13(rng: &mut Rng, size: usize) -> String {
+    let mut primes: Vec<u64> = (7..1000u64)
+        .filter(|n| (2..*n).take_while(|d| d * d <= *n).all(|d| n % d != 0))
+        .collect();
+    rng.shuffle(&mut primes);
+
+    let mut product = 1u64;
+    let buses: Vec<u64> = primes
+        .into_iter()
+        .take_while(|&bus| {
+            product = product.saturating_mul(bus);
+            product < 1_000_000_000_000_000
+        })
+        .take(size)
+        .collect();
+
+    let mut slots = vec![];
+    for (i, bus) in buses.iter().enumerate() {
+        if i > 0 {
+            slots.extend((0..rng.below(40)).map(|_| "x".to_owned()));
+        }
+        slots.push(bus.to_string());
+    }
+
+    format!("{}\n{}", rng.between(100_000, 1_000_000), slots.join(","))
+}
+
+/// At most nine floating bits per mask, as in the real inputs.
+fn day14(rng: &mut Rng, size: usize) -> String {
+    let mut out = vec![];
+    for _ in 0..size {
+        let mut mask: Vec<char> = (0..36)
+            .map(|_| if rng.chance(0.5) { '1' } else { '0' })
+            .collect();
+        for _ in 0..rng.below(10) {
+            mask[rng.below(36) as usize] = 'X';
+        }
+        out.push(format!("mask = {}", mask.into_iter().collect::<String>()));
+
+        for _ in 0..rng.between(1, 6) {
+            out.push(format!(
+                "mem[{}] = {}",
+                rng.below(1 << 16),
+                rng.below(1 << 30)
+            ));
+        }
+    }
+
+    lines(out)
+}
+
+fn day15(rng: &mut Rng, size: usize) -> String {
+    let mut numbers: Vec<u64> = (0..20).collect();
+    rng.shuffle(&mut numbers);
+    numbers.truncate(size);
+
+    numbers
+        .iter()
+        .map(|n| n.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// The field ranked `t` accepts values from bands `t..=size` and each column only holds
+/// values from its own field's band, so column candidates nest one inside the next.
+/// Invalid tickets carry a single value below every band.
+fn day16(rng: &mut Rng, size: usize) -> String {
+    const NAMES: [&str; 20] = [
+        "departure location",
+        "departure station",
+        "departure platform",
+        "departure track",
+        "departure date",
+        "departure time",
+        "arrival location",
+        "arrival station",
+        "arrival platform",
+        "arrival track",
+        "class",
+        "duration",
+        "price",
+        "route",
+        "row",
+        "seat",
+        "train",
+        "type",
+        "wagon",
+        "zone",
+    ];
+
+    let mut names = NAMES.to_vec();
+    rng.shuffle(&mut names);
+    names.truncate(size);
+
+    let k = size as u64;
+    let fields = names.iter().enumerate().map(|(rank, name)| {
+        let t = rank as u64 + 1;
+        let extra = 10 * (k + 2) + 5 * t;
+        format!(
+            "{}: {}-{} or {}-{}",
+            name,
+            10 * t,
+            10 * k + 9,
+            extra,
+            extra + 2
+        )
+    });
+
+    // column -> rank of its field
+    let mut columns: Vec<u64> = (1..=k).collect();
+    rng.shuffle(&mut columns);
+
+    let ticket = |rng: &mut Rng| {
+        columns
+            .iter()
+            .map(|rank| (10 * rank + rng.below(10)).to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let yours = ticket(rng).join(",");
+    let nearby: Vec<_> = (0..4 * k + 10)
+        .map(|_| {
+            let mut values = ticket(rng);
+            if rng.chance(0.25) {
+                values[rng.below(k) as usize] = rng.between(1, 9).to_string();
+            }
+            values.join(",")
+        })
+        .collect();
+
+    format!(
+        "{}\n\nyour ticket:\n{}\n\nnearby tickets:\n{}",
+        lines(fields),
+        yours,
+        lines(nearby)
+    )
+}
+
+fn day17(rng: &mut Rng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        (0..size)
+            .map(|_| if rng.chance(0.5) { '#' } else { '.' })
+            .collect::<String>()
+    }))
+}
+
+fn expression(rng: &mut Rng, depth: u32, digits: &mut u32) -> String {
+    let mut out = String::new();
+    for i in 0..rng.between(2, 4) {
+        if i > 0 {
+            out.push_str(if rng.chance(0.5) { " + " } else { " * " });
+        }
+        if depth < 2 && rng.chance(0.3) {
+            write!(out, "({})", expression(rng, depth + 1, digits)).unwrap();
+        } else {
+            *digits += 1;
+            write!(out, "{}", rng.between(1, 9)).unwrap();
+        }
+    }
+    out
+}
+
+/// Expressions are capped at twelve digits so every result fits comfortably.
+fn day18(rng: &mut Rng, size: usize) -> String {
+    lines((0..size).map(|_| loop {
+        let mut digits = 0;
+        let expr = expression(rng, 0, &mut digits);
+        if digits <= 12 {
+            break expr;
+        }
+    }))
+}
+
+#[derive(Debug, Clone)]
+enum Rule {
+    Char(char),
+    Alt(Vec<Vec<u8>>),
+}
+
+fn derive(rules: &HashMap<u8, Rule>, id: u8, rng: &mut Rng, out: &mut String) {
+    match &rules[&id] {
+        Rule::Char(c) => out.push(*c),
+        Rule::Alt(options) => {
+            for &sub in rng.choose(options) {
+                derive(rules, sub, rng, out);
+            }
+        }
+    }
+}
+
+/// Every rule matches strings of a single length, and rules 42 and 31 start with
+/// different letters, so the solver's greedy matching is exact. Messages are a mix of
+/// part 1 matches, part 2 only matches and noise.
+fn day19(rng: &mut Rng, size: usize) -> String {
+    let mut ids: Vec<u8> = (1..=255)
+        .filter(|id| ![8, 11, 31, 42].contains(id))
+        .collect();
+    rng.shuffle(&mut ids);
+    let mut ids = ids.into_iter();
+    let mut rules = HashMap::new();
+
+    let a = ids.next().unwrap();
+    let b = ids.next().unwrap();
+    rules.insert(a, Rule::Char('a'));
+    rules.insert(b, Rule::Char('b'));
+
+    let mut pool = vec![a, b];
+    for _ in 0..3 {
+        let level: Vec<u8> = (0..3)
+            .map(|_| {
+                let id = ids.next().unwrap();
+                let options = (0..rng.between(1, 2))
+                    .map(|_| vec![*rng.choose(&pool), *rng.choose(&pool)])
+                    .collect();
+                rules.insert(id, Rule::Alt(options));
+                id
+            })
+            .collect();
+        pool = level;
+    }
+
+    rules.insert(
+        42,
+        Rule::Alt(vec![
+            vec![a, *rng.choose(&pool)],
+            vec![a, *rng.choose(&pool)],
+        ]),
+    );
+    rules.insert(
+        31,
+        Rule::Alt(vec![
+            vec![b, *rng.choose(&pool)],
+            vec![b, *rng.choose(&pool)],
+        ]),
+    );
+    rules.insert(8, Rule::Alt(vec![vec![42]]));
+    rules.insert(11, Rule::Alt(vec![vec![42, 31]]));
+    rules.insert(0, Rule::Alt(vec![vec![8, 11]]));
+
+    let mut text: Vec<String> = rules
+        .iter()
+        .map(|(id, rule)| match rule {
+            Rule::Char(c) => format!("{}: \"{}\"", id, c),
+            Rule::Alt(options) => {
+                let options: Vec<_> = options
+                    .iter()
+                    .map(|seq| {
+                        seq.iter()
+                            .map(|id| id.to_string())
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    })
+                    .collect();
+                format!("{}: {}", id, options.join(" | "))
+            }
+        })
+        .collect();
+    text.sort_unstable();
+    rng.shuffle(&mut text);
+
+    let messages = (0..size).map(|_| {
+        let (forty_twos, thirty_ones) = match rng.below(3) {
+            0 => (2, 1),
+            1 => {
+                let thirty_ones = rng.between(1, 3);
+                (rng.between(thirty_ones + 1, 5), thirty_ones)
+            }
+            _ => {
+                let len = rng.between(10, 40);
+                return (0..len)
+                    .map(|_| if rng.chance(0.5) { 'a' } else { 'b' })
+                    .collect();
+            }
+        };
+
+        let mut message = String::new();
+        for _ in 0..forty_twos {
+            derive(&rules, 42, rng, &mut message);
+        }
+        for _ in 0..thirty_ones {
+            derive(&rules, 31, rng, &mut message);
+        }
+        message
+    });
+
+    format!("{}\n\n{}", lines(text), lines(messages))
+}
+
+const SEA_MONSTER: [&str; 3] = [
+    "                  # ",
+    "#    ##    ##    ###",
+    " #  #  #  #  #  #   ",
+];
+
+fn transform(tile: &[Vec<bool>], orientation: u64) -> Vec<Vec<bool>> {
+    let n = tile.len();
+    let mut out = tile.to_vec();
+    for _ in 0..orientation % 4 {
+        out = (0..n)
+            .map(|i| (0..n).map(|j| out[n - 1 - j][i]).collect())
+            .collect();
+    }
+    if orientation >= 4 {
+        out.iter_mut().for_each(|row| row.reverse());
+    }
+    out
+}
+
+/// Cuts a picture with a few sea monsters in it into `size`×`size` tiles. Every edge is
+/// given a distinct, non-palindromic pattern so tiles only ever match their neighbours.
+fn day20(rng: &mut Rng, size: usize) -> String {
+    let n = size;
+    let side = 8 * n;
+
+    let mut picture: Vec<Vec<bool>> = (0..side)
+        .map(|_| (0..side).map(|_| rng.chance(0.35)).collect())
+        .collect();
+    let mut taken = vec![vec![false; side]; side];
+    for _ in 0..rng.between(1, (n * n / 4).max(1) as u64) {
+        let row = rng.below((side - 2) as u64) as usize;
+        let col = rng.below((side - 19) as u64) as usize;
+        if (0..3).any(|i| (0..20).any(|j| taken[row + i][col + j])) {
+            continue;
+        }
+        for (i, line) in SEA_MONSTER.iter().enumerate() {
+            for (j, c) in line.chars().enumerate() {
+                taken[row + i][col + j] = true;
+                if c == '#' {
+                    picture[row + i][col + j] = true;
+                }
+            }
+        }
+    }
+
+    let edges = loop {
+        let corners: Vec<Vec<bool>> = (0..=n)
+            .map(|_| (0..=n).map(|_| rng.chance(0.5)).collect())
+            .collect();
+        let mut seen = HashSet::new();
+        let mut edge = |from: bool, to: bool| -> Option<Vec<bool>> {
+            for _ in 0..1000 {
+                let mut bits = vec![from];
+                bits.extend((0..8).map(|_| rng.chance(0.5)));
+                bits.push(to);
+
+                let reversed: Vec<_> = bits.iter().rev().copied().collect();
+                if bits != reversed && !seen.contains(&bits) && !seen.contains(&reversed) {
+                    seen.insert(bits.clone());
+                    return Some(bits);
+                }
+            }
+            None
+        };
+
+        // horizontal[r][c] runs along the top of tile (r, c), vertical[r][c] down its left
+        let horizontal: Option<Vec<Vec<_>>> = (0..=n)
+            .map(|r| {
+                (0..n)
+                    .map(|c| edge(corners[r][c], corners[r][c + 1]))
+                    .collect()
+            })
+            .collect();
+        let vertical: Option<Vec<Vec<_>>> = (0..n)
+            .map(|r| {
+                (0..=n)
+                    .map(|c| edge(corners[r][c], corners[r + 1][c]))
+                    .collect()
+            })
+            .collect();
+
+        if let (Some(horizontal), Some(vertical)) = (horizontal, vertical) {
+            break (horizontal, vertical);
+        }
+    };
+    let (horizontal, vertical) = edges;
+
+    let mut ids: Vec<u64> = (1000..10000).collect();
+    rng.shuffle(&mut ids);
+
+    let mut tiles = vec![];
+    for r in 0..n {
+        for c in 0..n {
+            let mut tile = vec![vec![false; 10]; 10];
+            for i in 0..10 {
+                tile[0][i] = horizontal[r][c][i];
+                tile[9][i] = horizontal[r + 1][c][i];
+                tile[i][0] = vertical[r][c][i];
+                tile[i][9] = vertical[r][c + 1][i];
+            }
+            for i in 0..8 {
+                for j in 0..8 {
+                    tile[i + 1][j + 1] = picture[8 * r + i][8 * c + j];
+                }
+            }
+
+            let tile = transform(&tile, rng.below(8));
+            let rows: Vec<String> = tile
+                .iter()
+                .map(|row| row.iter().map(|&on| if on { '#' } else { '.' }).collect())
+                .collect();
+            tiles.push(format!("Tile {}:\n{}", ids[r * n + c], lines(rows)));
+        }
+    }
+
+    rng.shuffle(&mut tiles);
+    tiles.join("\n\n")
+}
+
+/// Whether eliminating resolved allergens one at a time pins each allergen to the
+/// ingredient that actually contains it.
+fn allergens_resolve(foods: &[(Vec<usize>, Vec<usize>)], dangerous: &[usize]) -> bool {
+    let mut candidates: Vec<Option<HashSet<usize>>> = vec![None; dangerous.len()];
+    for (ingredients, allergens) in foods {
+        let ingredients: HashSet<_> = ingredients.iter().copied().collect();
+        for &allergen in allergens {
+            let narrowed = match &candidates[allergen] {
+                Some(set) => set.intersection(&ingredients).copied().collect(),
+                None => ingredients.clone(),
+            };
+            candidates[allergen] = Some(narrowed);
+        }
+    }
+
+    let mut candidates: Vec<HashSet<usize>> = match candidates.into_iter().collect() {
+        Some(candidates) => candidates,
+        None => return false,
+    };
+    loop {
+        let resolved: Vec<usize> = candidates
+            .iter()
+            .filter(|set| set.len() == 1)
+            .flat_map(|set| set.iter().copied())
+            .collect();
+        if resolved.len() == candidates.len() {
+            break;
+        }
+
+        let before: usize = candidates.iter().map(|set| set.len()).sum();
+        for set in candidates.iter_mut().filter(|set| set.len() > 1) {
+            resolved.iter().for_each(|r| {
+                set.remove(r);
+            });
+        }
+        if candidates.iter().map(|set| set.len()).sum::<usize>() == before {
+            return false;
+        }
+    }
+
+    candidates
+        .iter()
+        .zip(dangerous)
+        .all(|(set, ingredient)| set.contains(ingredient))
+}
+
+fn day21(rng: &mut Rng, size: usize) -> String {
+    const ALLERGENS: [&str; 8] = [
+        "dairy",
+        "eggs",
+        "fish",
+        "nuts",
+        "peanuts",
+        "sesame",
+        "shellfish",
+        "soy",
+    ];
+
+    let (names, allergens, foods) = loop {
+        let allergens = rng.between(2, ALLERGENS.len() as u64) as usize;
+        let ingredients = allergens * rng.between(3, 6) as usize;
+
+        let mut names = HashSet::new();
+        while names.len() < ingredients {
+            let len = rng.between(4, 8) as usize;
+            names.insert(word(rng, len));
+        }
+        let mut names: Vec<String> = names.into_iter().collect();
+        names.sort_unstable();
+        rng.shuffle(&mut names);
+
+        // allergen i is in ingredient i; the rest are safe
+        let dangerous: Vec<usize> = (0..allergens).collect();
+        let mut foods = vec![];
+        let mut attempts = 0;
+        while foods.len() < size.max(allergens) || !allergens_resolve(&foods, &dangerous) {
+            attempts += 1;
+            if attempts > 20 * size.max(allergens) {
+                break;
+            }
+
+            let mut food: Vec<usize> = (allergens..ingredients)
+                .filter(|_| rng.chance(0.4))
+                .collect();
+            let mut listed = vec![];
+            for allergen in 0..allergens {
+                if rng.chance(0.4) {
+                    food.push(allergen);
+                    if rng.chance(0.7) {
+                        listed.push(allergen);
+                    }
+                }
+            }
+            if listed.is_empty() {
+                let allergen = rng.below(allergens as u64) as usize;
+                if !food.contains(&allergen) {
+                    food.push(allergen);
+                }
+                listed.push(allergen);
+            }
+
+            rng.shuffle(&mut food);
+            foods.push((food, listed));
+        }
+
+        if allergens_resolve(&foods, &dangerous) {
+            break (names, allergens, foods);
+        }
+    };
+
+    let mut labels = ALLERGENS.to_vec();
+    rng.shuffle(&mut labels);
+    labels.truncate(allergens);
+
+    lines(foods.into_iter().map(|(food, listed)| {
+        let food: Vec<_> = food.iter().map(|&i| names[i].as_str()).collect();
+        let listed: Vec<_> = listed.iter().map(|&i| labels[i]).collect();
+        format!("{} (contains {})", food.join(" "), listed.join(", "))
+    }))
+}
+
+/// Whether a game of plain Combat between these decks ever ends, rather than going back to
+/// a deal it has already played from.
+fn combat_ends(first: &[u64], second: &[u64]) -> bool {
+    let mut first: VecDeque<_> = first.iter().copied().collect();
+    let mut second: VecDeque<_> = second.iter().copied().collect();
+    let mut seen = HashSet::new();
+
+    while let (Some(&a), Some(&b)) = (first.front(), second.front()) {
+        if !seen.insert((first.clone(), second.clone())) {
+            return false;
+        }
+        first.pop_front();
+        second.pop_front();
+        if a > b {
+            first.push_back(a);
+            first.push_back(b);
+        } else {
+            second.push_back(b);
+            second.push_back(a);
+        }
+    }
+    true
+}
+
+/// Deals are reshuffled until plain Combat finishes on them, since some loop forever.
+fn day22(rng: &mut Rng, size: usize) -> String {
+    let mut cards: Vec<u64> = (1..=2 * size as u64).collect();
+    loop {
+        rng.shuffle(&mut cards);
+        if combat_ends(&cards[..size], &cards[size..]) {
+            break;
+        }
+    }
+    let (first, second) = cards.split_at(size);
+
+    format!(
+        "Player 1:\n{}\n\nPlayer 2:\n{}",
+        lines(first),
+        lines(second)
+    )
+}
+
+fn day23(rng: &mut Rng) -> String {
+    let mut cups: Vec<u64> = (1..=9).collect();
+    rng.shuffle(&mut cups);
+    cups.iter().map(|cup| cup.to_string()).collect()
+}
+
+fn day24(rng: &mut Rng, size: usize) -> String {
+    lines((0..size).map(|_| {
+        (0..rng.between(1, 20))
+            .map(|_| *rng.choose(&["e", "se", "sw", "w", "nw", "ne"]))
+            .collect::<String>()
+    }))
+}
+
+fn day25(rng: &mut Rng, size: usize) -> String {
+    let public_key = |loops: u64| number_theory::pow_mod(7, loops, 20201227);
+    let card = public_key(rng.between(1, size as u64));
+    let door = public_key(rng.between(1, size as u64));
+    format!("{}\n{}", card, door)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{parts, solve};
+
+    #[test]
+    fn test_deterministic() {
+        for day in 1..=25 {
+            assert_eq!(generate(day, 3, 10), generate(day, 3, 10));
+        }
+        assert_ne!(generate(7, 1, 50), generate(7, 2, 50));
+        assert_eq!(generate(26, 0, 10), Err(Error::UnknownDay(26)));
+    }
+
+    /// Small inputs from a few seeds, and a large one that runs into most days' caps.
+    #[test]
+    fn test_solvable() {
+        for day in 1..=25 {
+            for &(size, seeds) in &[(20, 3), (500, 1)] {
+                for seed in 0..seeds {
+                    let input = generate(day, seed, size).unwrap();
+                    for &part in parts(day) {
+                        // the 30,000,000 and 10,000,000 step parts are too slow to repeat here
+                        if (day == 15 || day == 23) && part == 2 {
+                            continue;
+                        }
+                        assert!(
+                            solve(day, part, &input).is_ok(),
+                            "day {} part {} seed {} size {}:\n{}",
+                            day,
+                            part,
+                            seed,
+                            size,
+                            input
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Days 11 and 22 used to draw inputs their solvers never finish, so these run at the
+    /// CLI's default size and at small ones, each under a time limit rather than a hang.
+    /// Recursive Combat always ends, and is slow at full size, so day 22 only plays part 1.
+    #[test]
+    fn test_terminates() {
+        use std::{sync::mpsc, thread, time::Duration};
+
+        for &(day, part) in &[(11, 1), (11, 2), (22, 1)] {
+            for &(size, seeds) in &[(1, 8), (2, 8), (3, 8), (5, 8), (8, 8), (100, 4)] {
+                for seed in 0..seeds {
+                    let input = generate(day, seed, size).unwrap();
+                    let (send, receive) = mpsc::channel();
+                    let run = input.clone();
+                    thread::spawn(move || send.send(solve(day, part, &run).is_ok()));
+                    assert_eq!(
+                        receive.recv_timeout(Duration::from_secs(60)),
+                        Ok(true),
+                        "day {} part {} seed {} size {}:\n{}",
+                        day,
+                        part,
+                        seed,
+                        size,
+                        input
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_day8_single_fix() {
+        let input = generate(8, 11, 200).unwrap();
+        let answer = solve(8, 2, &input).unwrap();
+        assert_eq!(answer.field("index"), Some(&200usize.into()));
+    }
+}
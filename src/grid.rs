@@ -0,0 +1,316 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::error::ParseError;
+
+/// `(row, column)`, counted from the top left.
+pub type Pos = (usize, usize);
+
+/// `(row, column)` steps to the four orthogonal neighbours.
+pub const DIRECTIONS4: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// `(row, column)` steps to all eight neighbours.
+pub const DIRECTIONS8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A rectangular grid stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(height: usize, width: usize, f: impl FnMut(Pos) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(f)
+            .collect();
+
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses a character map with one row per line, mapping each character with `cell`.
+    pub fn parse(
+        input: &str,
+        cell: impl FnMut(char) -> Option<T>,
+        expected: &str,
+    ) -> Result<Self, ParseError> {
+        Grid::parse_in(input, input, cell, expected)
+    }
+
+    /// Like `parse`, for a map that is a slice of a larger `source`; errors are reported
+    /// relative to `source`.
+    pub fn parse_in(
+        source: &str,
+        map: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+        expected: &str,
+    ) -> Result<Self, ParseError> {
+        let width = map.lines().next().map_or(0, |line| line.chars().count());
+        let mut height = 0;
+        let mut cells = Vec::with_capacity(map.len());
+
+        for line in map.lines() {
+            if line.chars().count() != width {
+                return Err(ParseError::at(
+                    source,
+                    line,
+                    format!("a row {} cells wide", width),
+                ));
+            }
+
+            for (i, c) in line.char_indices() {
+                cells.push(cell(c).ok_or_else(|| ParseError::at(source, &line[i..], expected))?);
+            }
+            height += 1;
+        }
+
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index_of(&self, (row, col): Pos) -> Option<usize> {
+        if row < self.height && col < self.width {
+            Some(row * self.width + col)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.index_of(pos).map(move |i| &mut self.cells[i])
+    }
+
+    /// Stores `value` at `pos`, returning it back if `pos` is outside the grid.
+    pub fn set(&mut self, pos: Pos, value: T) -> Result<(), T> {
+        match self.get_mut(pos) {
+            Some(cell) => {
+                *cell = value;
+                Ok(())
+            }
+            None => Err(value),
+        }
+    }
+
+    /// `pos` moved by `step`, if that is still inside the grid.
+    pub fn offset(&self, (row, col): Pos, (dr, dc): (isize, isize)) -> Option<Pos> {
+        let row = row as isize + dr;
+        let col = col as isize + dc;
+        if (0..self.height as isize).contains(&row) && (0..self.width as isize).contains(&col) {
+            Some((row as usize, col as usize))
+        } else {
+            None
+        }
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.width.max(1))
+    }
+
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRECTIONS4
+            .iter()
+            .filter_map(move |&step| self.offset(pos, step))
+    }
+
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRECTIONS8
+            .iter()
+            .filter_map(move |&step| self.offset(pos, step))
+    }
+
+    /// Every position from `pos` (exclusive) in direction `step` until the edge.
+    pub fn ray(&self, pos: Pos, step: (isize, isize)) -> impl Iterator<Item = Pos> + '_ {
+        std::iter::successors(self.offset(pos, step), move |&pos| self.offset(pos, step))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(height: usize, width: usize, value: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        Grid::from_fn(self.width, self.height, |(row, col)| {
+            self[(self.height - 1 - col, row)].clone()
+        })
+    }
+
+    /// Mirrors left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |(row, col)| {
+            self[(row, self.width - 1 - col)].clone()
+        })
+    }
+
+    /// Mirrors top to bottom.
+    pub fn flip_vertical(&self) -> Self {
+        Grid::from_fn(self.height, self.width, |(row, col)| {
+            self[(self.height - 1 - row, col)].clone()
+        })
+    }
+
+    /// All eight rotations and reflections, starting with the grid itself.
+    pub fn orientations(&self) -> Vec<Self> {
+        let mut out = Vec::with_capacity(8);
+        for start in [self.clone(), self.flip_horizontal()].iter() {
+            let mut grid = start.clone();
+            for _ in 0..4 {
+                let next = grid.rotate_clockwise();
+                out.push(grid);
+                grid = next;
+            }
+        }
+        out
+    }
+
+    pub fn subgrid(&self, (row, col): Pos, height: usize, width: usize) -> Self {
+        Grid::from_fn(height, width, |(r, c)| self[(row + r, col + c)].clone())
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match self.get(pos) {
+            Some(cell) => cell,
+            None => panic!("{:?} is outside a {}x{} grid", pos, self.height, self.width),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (height, width) = (self.height, self.width);
+        match self.get_mut(pos) {
+            Some(cell) => cell,
+            None => panic!("{:?} is outside a {}x{} grid", pos, height, width),
+        }
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn digits(input: &str) -> Grid<u32> {
+        Grid::parse(input, |c| c.to_digit(10), "a digit").unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits("123\n456");
+        assert_eq!((grid.height(), grid.width()), (2, 3));
+        assert_eq!(grid.get((1, 2)), Some(&6));
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.to_string(), "123\n456");
+
+        let err = Grid::parse("12\n3x", |c| c.to_digit(10), "a digit").unwrap_err();
+        assert_eq!(err, ParseError::new(2, 2, "a digit"));
+        let err = Grid::parse("12\n345", |c| c.to_digit(10), "a digit").unwrap_err();
+        assert_eq!(err, ParseError::new(2, 1, "a row 2 cells wide"));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits("123\n456\n789");
+        assert_eq!(grid.neighbours4((0, 0)).count(), 2);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(
+            grid.ray((0, 0), (1, 1))
+                .map(|pos| grid[pos])
+                .collect::<Vec<_>>(),
+            vec![5, 9]
+        );
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5, 8]);
+    }
+
+    #[test]
+    fn test_orientations() {
+        let grid = digits("12\n34\n56");
+        assert_eq!(grid.rotate_clockwise().to_string(), "531\n642");
+        assert_eq!(grid.flip_horizontal().to_string(), "21\n43\n65");
+        assert_eq!(grid.flip_vertical().to_string(), "56\n34\n12");
+
+        let all = grid.orientations();
+        assert_eq!(all.len(), 8);
+        assert_eq!(all[4], grid.flip_horizontal());
+        assert!(all.contains(&grid.flip_vertical()));
+        assert_eq!(grid.subgrid((1, 0), 2, 1).to_string(), "3\n5");
+    }
+}
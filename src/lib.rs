@@ -1,32 +1,175 @@
-#![feature(str_split_once)]
-#![feature(split_inclusive)]
+use std::io::BufRead;
 
 use aoc_runner_derive::aoc_lib;
 
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day2;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
+mod answer;
+mod error;
+#[macro_use]
+mod puzzle;
+mod rng;
+#[macro_use]
+pub mod trace;
+
+pub use answer::Answer;
+pub use context::Context;
+pub use error::{Error, ParseError};
+pub use grid::Grid;
+pub use puzzle::{Implementation, Puzzle};
+pub use rng::Rng;
+
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+
+pub mod animate;
+pub mod assignment;
+pub mod automaton;
+pub mod batch;
+pub mod bigint;
+pub mod checked;
+pub mod compare;
+pub mod context;
+pub mod differential;
+pub mod examples;
+pub mod explain;
+pub mod frames;
+pub mod generate;
+pub mod grid;
+pub mod number_theory;
+pub mod profile;
+pub mod stream;
+pub mod verify;
+
+/// Days with a solution, in order.
+pub fn days() -> impl Iterator<Item = u8> {
+    1..=25
+}
+
+/// Parts solved for `day`; empty if the day is unknown.
+pub fn parts(day: u8) -> &'static [u8] {
+    with_puzzle!(day, P => &[1, 2][..P::PARTS as usize], _ => &[])
+}
+
+/// Parses `input` and solves one part of a day, without going through cargo-aoc.
+pub fn solve(day: u8, part: u8, input: &str) -> Result<Answer, Error> {
+    // the generators expect what cargo-aoc gives them: no trailing newline, and no CR
+    let input = &stream::normalize(input);
+
+    with_puzzle!(day, P => P::solve(part, input), _ => Err(Error::UnknownDay(day)))
+}
+
+/// Like [`solve`], under `context`: with progress reports from the long-running days, and
+/// stopping early if it is cancelled or runs out of budget.
+pub fn solve_with(day: u8, part: u8, input: &str, context: &Context) -> Result<Answer, Error> {
+    context::run(context, || solve(day, part, input)).and_then(|result| result)
+}
+
+/// Like [`solve`], parsing straight from `reader`. Days with a line- or block-oriented
+/// input never hold all of it in memory at once.
+pub fn solve_reader(day: u8, part: u8, reader: &mut dyn BufRead) -> Result<Answer, Error> {
+    with_puzzle!(day, P => P::solve_reader(part, reader), _ => Err(Error::UnknownDay(day)))
+}
 
 aoc_lib! { year = 2020 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(
+            solve(1, 1, "1721\n979\n366\n299\n675\n1456\n"),
+            Ok(514579.into())
+        );
+        assert_eq!(solve(25, 1, "5764801\n17807724"), Ok(14897079.into()));
+        assert_eq!(solve(26, 1, ""), Err(Error::UnknownDay(26)));
+        assert_eq!(
+            solve(25, 2, ""),
+            Err(Error::UnknownPart { day: 25, part: 2 })
+        );
+        assert!(matches!(solve(1, 1, "12\nabc"), Err(Error::Parse(_))));
+    }
+
+    #[test]
+    fn test_solve_reader() {
+        // the line- and block-oriented days stream; day 25 reads its input whole
+        for example in examples::EXAMPLES
+            .iter()
+            .filter(|example| [1, 2, 4, 5, 8, 10, 12, 14, 18, 24, 25].contains(&example.day))
+        {
+            for &part in parts(example.day) {
+                if example.expected(part).is_none() {
+                    continue;
+                }
+
+                let input = format!("{}\n\n", example.input.replace('\n', "\r\n"));
+                assert_eq!(
+                    solve_reader(example.day, part, &mut input.as_bytes()),
+                    solve(example.day, part, example.input)
+                );
+            }
+        }
+
+        assert_eq!(
+            solve_reader(1, 1, &mut &b"1721\n979\nabc\n"[..]),
+            Err(Error::Parse(ParseError::new(3, 1, "an expense amount")))
+        );
+        assert_eq!(
+            solve_reader(4, 1, &mut &b"byr:1937\n\nbyr:1937 iyr2017"[..]),
+            Err(Error::Parse(ParseError::new(
+                3,
+                10,
+                "a passport field like `byr:1937`"
+            )))
+        );
+    }
+
+    #[test]
+    fn test_normalized_input() {
+        // part 1 only: some part 2s take long enough without running three times over
+        for day in days() {
+            let input = generate::generate(day, 0, 10).unwrap();
+            let expected = solve(day, 1, &input);
+            for variant in [
+                format!("{}\n\n", input),
+                format!("\u{feff}{}\r\n\r\n", input.replace('\n', "\r\n")),
+            ] {
+                assert_eq!(solve(day, 1, &variant), expected, "day {}", day);
+                assert_eq!(
+                    solve_reader(day, 1, &mut variant.as_bytes()),
+                    expected,
+                    "day {}",
+                    day
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_parts() {
+        assert_eq!(days().map(|day| parts(day).len()).sum::<usize>(), 49);
+    }
+}
@@ -0,0 +1,202 @@
+//! Modular arithmetic: the Chinese remainder theorem day 13 lines its buses up with, and the
+//! exponentiation and discrete logarithm behind day 25's handshake. Moduli are `u64`, with
+//! products worked out in `u128` so nothing in between overflows.
+
+use std::collections::HashMap;
+
+use crate::bigint::Natural;
+
+pub fn gcd(a: u64, b: u64) -> u64 {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
+
+/// `(g, x, y)` with `g = gcd(a, b) = a * x + b * y`.
+pub fn extended_gcd(a: u64, b: u64) -> (u64, i128, i128) {
+    match b {
+        0 => (a, 1, 0),
+        _ => {
+            let (g, x, y) = extended_gcd(b, a % b);
+            (g, y, x - (a / b) as i128 * y)
+        }
+    }
+}
+
+/// `a * b mod modulus`. Panics if `modulus` is zero, as `%` does.
+pub fn mul_mod(a: u64, b: u64, modulus: u64) -> u64 {
+    (a as u128 * b as u128 % modulus as u128) as u64
+}
+
+/// `base^exp mod modulus`, by repeated squaring. Panics if `modulus` is zero, as `%` does.
+pub fn pow_mod(mut base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut result = 1 % modulus;
+    base %= modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exp >>= 1;
+    }
+    result
+}
+
+/// The `x` in `0..modulus` with `a * x = 1 (mod modulus)`, if `a` and `modulus` are coprime.
+pub fn inverse_mod(a: u64, modulus: u64) -> Option<u64> {
+    if modulus == 0 {
+        return None;
+    }
+    match extended_gcd(a % modulus, modulus) {
+        (1, x, _) => Some(x.rem_euclid(modulus as i128) as u64),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrtError {
+    /// Two of the congruences contradict each other.
+    NoSolution,
+    /// The answer, or the combined modulus, doesn't fit in the integer type asked for.
+    Overflow,
+}
+
+/// The smallest `x` satisfying every `x = remainder (mod modulus)` in `congruences`, along with
+/// the modulus of all of them together, so every solution is `x` plus a multiple of it. The
+/// moduli needn't be coprime. Counts in `N`, so a [`crate::bigint::BigUint`] never overflows.
+pub fn crt<N: Natural>(congruences: &[(u64, u64)]) -> Result<(N, N), CrtError> {
+    let mut x = N::default();
+    let mut m = N::from(1);
+
+    for &(remainder, modulus) in congruences {
+        if modulus == 0 {
+            return Err(CrtError::NoSolution);
+        }
+
+        // x + m * k = remainder (mod modulus), so m * k = diff
+        let diff = ((remainder % modulus) as u128 + modulus as u128 - x.rem_u64(modulus) as u128)
+            % modulus as u128;
+        let diff = diff as u64;
+        let m_mod = m.rem_u64(modulus);
+        let g = gcd(m_mod, modulus);
+        if !diff.is_multiple_of(g) {
+            return Err(CrtError::NoSolution);
+        }
+
+        let step = modulus / g;
+        let k = match inverse_mod(m_mod / g, step) {
+            Some(inverse) => mul_mod(diff / g, inverse, step),
+            None => 0,
+        };
+        x = m
+            .clone()
+            .checked_mul(N::from(k))
+            .and_then(|mk| x.checked_add(mk))
+            .ok_or(CrtError::Overflow)?;
+        m = m.checked_mul(N::from(step)).ok_or(CrtError::Overflow)?;
+    }
+
+    Ok((x, m))
+}
+
+/// The smallest `x` with `base^x = target (mod modulus)`, by baby-step giant-step. Takes about
+/// `sqrt(modulus)` time and memory. If `base` isn't coprime to `modulus`, only answers below
+/// `sqrt(modulus)` are found.
+pub fn discrete_log(base: u64, target: u64, modulus: u64) -> Option<u64> {
+    if modulus == 0 {
+        return None;
+    }
+    let target = target % modulus;
+    let root = modulus.isqrt();
+    let m = if root * root == modulus {
+        root
+    } else {
+        root + 1
+    };
+
+    // base^j for j < m, keeping the smallest j for each value
+    let mut baby = HashMap::new();
+    let mut value = 1 % modulus;
+    for j in 0..m {
+        baby.entry(value).or_insert(j);
+        value = mul_mod(value, base, modulus);
+    }
+    if let Some(&j) = baby.get(&target) {
+        return Some(j);
+    }
+
+    // target * base^(-m * i) = base^j means x = m * i + j
+    let giant = inverse_mod(pow_mod(base, m, modulus), modulus)?;
+    let mut gamma = target;
+    for i in 0..m {
+        if let Some(j) = baby.get(&gamma) {
+            return Some(i * m + j);
+        }
+        gamma = mul_mod(gamma, giant, modulus);
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bigint::BigUint;
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(7, 0), 7);
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+
+        assert_eq!(pow_mod(7, 8, 20201227), 5764801);
+        assert_eq!(pow_mod(5764801, 11, 20201227), 14897079);
+        assert_eq!(pow_mod(2, 64, u64::MAX), 1);
+        assert_eq!(pow_mod(3, 0, 1), 0);
+
+        assert_eq!(inverse_mod(3, 11), Some(4));
+        assert_eq!(inverse_mod(13, 1), Some(0));
+        assert_eq!(inverse_mod(6, 9), None);
+        assert_eq!(inverse_mod(3, 0), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt::<u64>(&[(2, 3), (3, 5), (2, 7)]), Ok((23, 105)));
+        assert_eq!(crt::<u64>(&[]), Ok((0, 1)));
+
+        // moduli sharing a factor: consistent, and not
+        assert_eq!(crt::<u64>(&[(3, 4), (5, 6)]), Ok((11, 12)));
+        assert_eq!(crt::<u64>(&[(0, 4), (1, 6)]), Err(CrtError::NoSolution));
+        assert_eq!(crt::<u64>(&[(1, 0)]), Err(CrtError::NoSolution));
+
+        // a modulus above 2^63, where the remainders add up past u64
+        let large = 18446744073709551557;
+        assert_eq!(crt::<u64>(&[(large - 1, large)]), Ok((large - 1, large)));
+        let (x, m) = crt::<BigUint>(&[(1, 2), (large - 2, large)]).unwrap();
+        assert_eq!((x.rem_u64(2), x.rem_u64(large)), (1, large - 2));
+        assert_eq!(m, BigUint::from(large) * BigUint::from(2));
+
+        let primes = [(1, 4294967291), (2, 4294967279), (3, 4294967231)];
+        assert_eq!(crt::<u64>(&primes), Err(CrtError::Overflow));
+        let (x, m) = crt::<BigUint>(&primes).unwrap();
+        assert!(x < m);
+        for (remainder, modulus) in primes {
+            assert_eq!(x.rem_u64(modulus), remainder);
+        }
+    }
+
+    #[test]
+    fn test_discrete_log() {
+        assert_eq!(discrete_log(7, 5764801, 20201227), Some(8));
+        assert_eq!(discrete_log(7, 17807724, 20201227), Some(11));
+        assert_eq!(discrete_log(7, 1, 20201227), Some(0));
+        assert_eq!(discrete_log(3, 13, 17), Some(4));
+        // 2 only reaches 1, 2 and 4 mod 7
+        assert_eq!(discrete_log(2, 3, 7), None);
+        assert_eq!(discrete_log(7, 1, 0), None);
+        // 2 isn't invertible mod 8, but 2^2 = 4 is still found
+        assert_eq!(discrete_log(2, 4, 8), Some(2));
+    }
+}
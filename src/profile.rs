@@ -0,0 +1,145 @@
+use std::{
+    fmt::Write,
+    time::{Duration, Instant},
+};
+
+use crate::{stream, Error, Puzzle};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    /// Summarises a non-empty set of samples.
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort();
+        Stats {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Wall time spent in one day's generator and in each of its parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub day: u8,
+    pub parse: Stats,
+    pub parts: Vec<(u8, Stats)>,
+}
+
+fn time<T>(repeat: usize, mut f: impl FnMut() -> T) -> (T, Stats) {
+    let mut samples = Vec::with_capacity(repeat);
+    let mut result = None;
+    for _ in 0..repeat.max(1) {
+        let start = Instant::now();
+        result = Some(f());
+        samples.push(start.elapsed());
+    }
+
+    (result.unwrap(), Stats::from_samples(samples))
+}
+
+fn profile_puzzle<P: Puzzle>(input: &str, repeat: usize) -> Result<Profile, Error> {
+    let (parsed, parse) = time(repeat, || P::parse(input));
+    let parsed = parsed?;
+
+    let parts = (1..=P::PARTS)
+        .map(|part| {
+            let (answer, stats) = time(repeat, || P::solve_part(&parsed, part));
+            answer.map(|_| (part, stats))
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Profile {
+        day: P::DAY,
+        parse,
+        parts,
+    })
+}
+
+/// Parses and solves `day` `repeat` times, timing each phase separately. Fails with the error
+/// from the first phase that has one, rather than timing a part that never answered.
+pub fn profile(day: u8, input: &str, repeat: usize) -> Result<Profile, Error> {
+    let input = &stream::normalize(input);
+    with_puzzle!(day, P => profile_puzzle::<P>(input, repeat), _ => Err(Error::UnknownDay(day)))
+}
+
+fn millis(d: Duration) -> String {
+    format!("{:.3}", d.as_secs_f64() * 1000.0)
+}
+
+/// Renders one row per phase, with times in milliseconds.
+pub fn table(profiles: &[Profile]) -> String {
+    let mut out = format!(
+        "{:>3}  {:<6}  {:>12}  {:>12}  {:>12}\n",
+        "day", "phase", "min ms", "median ms", "max ms"
+    );
+
+    for profile in profiles {
+        let phases = std::iter::once(("parse".to_owned(), profile.parse)).chain(
+            profile
+                .parts
+                .iter()
+                .map(|(part, stats)| (format!("part {}", part), *stats)),
+        );
+
+        for (phase, stats) in phases {
+            writeln!(
+                out,
+                "{:>3}  {:<6}  {:>12}  {:>12}  {:>12}",
+                profile.day,
+                phase,
+                millis(stats.min),
+                millis(stats.median),
+                millis(stats.max)
+            )
+            .unwrap();
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let ms = Duration::from_millis;
+        assert_eq!(
+            Stats::from_samples(vec![ms(5), ms(1), ms(3)]),
+            Stats {
+                min: ms(1),
+                median: ms(3),
+                max: ms(5)
+            }
+        );
+    }
+
+    #[test]
+    fn test_profile() {
+        let day25 = profile(25, "5764801\n17807724\n", 3).unwrap();
+        assert_eq!(day25.parts.len(), 1);
+
+        let table = table(&[day25]);
+        assert_eq!(table.lines().count(), 3);
+        assert!(table.lines().nth(2).unwrap().starts_with(" 25  part 1"));
+
+        assert_eq!(profile(0, "", 1), Err(Error::UnknownDay(0)));
+        if cfg!(feature = "checked") {
+            assert_eq!(
+                profile(8, "acc +30000\nacc +30000", 2),
+                Err(Error::Overflow {
+                    day: 8,
+                    operation: "accumulator"
+                })
+            );
+        }
+    }
+}
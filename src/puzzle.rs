@@ -0,0 +1,230 @@
+use std::io::BufRead;
+
+use crate::explain::Step;
+use crate::{checked, stream, Answer, Error, ParseError};
+
+/// A named way of solving one part of a day, checked against the others by
+/// [`crate::differential`].
+pub struct Implementation<I> {
+    pub part: u8,
+    pub name: &'static str,
+    pub solve: fn(&I) -> Answer,
+}
+
+/// One day's puzzle: a parser for the raw input and a solver for each part.
+pub trait Puzzle {
+    const DAY: u8;
+    /// Only day 25 has a single part.
+    const PARTS: u8 = 2;
+
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    /// Parses straight from a reader. Days whose input can be parsed a line or a block at
+    /// a time override this to avoid holding it all in memory; the rest read it whole.
+    fn read(reader: &mut dyn BufRead) -> Result<Self::Input, Error> {
+        let mut input = String::new();
+        reader
+            .read_to_string(&mut input)
+            .map_err(|e| Error::Io(e.to_string()))?;
+        Ok(Self::parse(&stream::normalize(&input))?)
+    }
+
+    fn part1(input: &Self::Input) -> Answer;
+    fn part2(input: &Self::Input) -> Answer;
+
+    /// Other implementations of the parts, usually slow but obviously right. The first one
+    /// registered for a part is the oracle that the rest, and `part1`/`part2`, must agree with.
+    fn implementations() -> Vec<Implementation<Self::Input>> {
+        vec![]
+    }
+
+    /// How `part` arrives at its answer, for the days that can say.
+    fn explain(_input: &Self::Input, _part: u8) -> Option<Vec<Step>> {
+        None
+    }
+
+    /// Rejects an input that parses but that `part` can't be solved for.
+    fn check(_input: &Self::Input, _part: u8) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn has_part(part: u8) -> bool {
+        (1..=Self::PARTS).contains(&part)
+    }
+
+    fn solve_part(input: &Self::Input, part: u8) -> Result<Answer, Error> {
+        if Self::has_part(part) {
+            Self::check(input, part)?;
+        }
+        match part {
+            1 => checked::catch(|| Self::part1(input)),
+            2 if Self::has_part(2) => checked::catch(|| Self::part2(input)),
+            _ => Err(Error::UnknownPart {
+                day: Self::DAY,
+                part,
+            }),
+        }
+    }
+
+    fn solve(part: u8, input: &str) -> Result<Answer, Error> {
+        if !Self::has_part(part) {
+            return Err(Error::UnknownPart {
+                day: Self::DAY,
+                part,
+            });
+        }
+
+        Self::solve_part(&Self::parse(input)?, part)
+    }
+
+    fn solve_reader(part: u8, reader: &mut dyn BufRead) -> Result<Answer, Error> {
+        if !Self::has_part(part) {
+            return Err(Error::UnknownPart {
+                day: Self::DAY,
+                part,
+            });
+        }
+
+        Self::solve_part(&Self::read(reader)?, part)
+    }
+}
+
+/// Runs `$body` with `$p` bound to the `Puzzle` type for `$day`, or evaluates `$otherwise`.
+macro_rules! with_puzzle {
+    ($day:expr, $p:ident => $body:expr, _ => $otherwise:expr) => {
+        match $day {
+            1 => {
+                type $p = crate::day1::Day1;
+                $body
+            }
+            2 => {
+                type $p = crate::day2::Day2;
+                $body
+            }
+            3 => {
+                type $p = crate::day3::Day3;
+                $body
+            }
+            4 => {
+                type $p = crate::day4::Day4;
+                $body
+            }
+            5 => {
+                type $p = crate::day5::Day5;
+                $body
+            }
+            6 => {
+                type $p = crate::day6::Day6;
+                $body
+            }
+            7 => {
+                type $p = crate::day7::Day7;
+                $body
+            }
+            8 => {
+                type $p = crate::day8::Day8;
+                $body
+            }
+            9 => {
+                type $p = crate::day9::Day9;
+                $body
+            }
+            10 => {
+                type $p = crate::day10::Day10;
+                $body
+            }
+            11 => {
+                type $p = crate::day11::Day11;
+                $body
+            }
+            12 => {
+                type $p = crate::day12::Day12;
+                $body
+            }
+            13 => {
+                type $p = crate::day13::Day13;
+                $body
+            }
+            14 => {
+                type $p = crate::day14::Day14;
+                $body
+            }
+            15 => {
+                type $p = crate::day15::Day15;
+                $body
+            }
+            16 => {
+                type $p = crate::day16::Day16;
+                $body
+            }
+            17 => {
+                type $p = crate::day17::Day17;
+                $body
+            }
+            18 => {
+                type $p = crate::day18::Day18;
+                $body
+            }
+            19 => {
+                type $p = crate::day19::Day19;
+                $body
+            }
+            20 => {
+                type $p = crate::day20::Day20;
+                $body
+            }
+            21 => {
+                type $p = crate::day21::Day21;
+                $body
+            }
+            22 => {
+                type $p = crate::day22::Day22;
+                $body
+            }
+            23 => {
+                type $p = crate::day23::Day23;
+                $body
+            }
+            24 => {
+                type $p = crate::day24::Day24;
+                $body
+            }
+            25 => {
+                type $p = crate::day25::Day25;
+                $body
+            }
+            _ => $otherwise,
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{day1::Day1, day25::Day25};
+
+    fn run<P: Puzzle>(input: &str) -> Vec<Answer> {
+        let parsed = P::parse(input).unwrap();
+        (1..=P::PARTS)
+            .map(|part| P::solve_part(&parsed, part).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_generic() {
+        assert_eq!(
+            run::<Day1>("1721\n979\n366\n299\n675\n1456"),
+            vec![Answer::from(514579), Answer::from(241861950)]
+        );
+        assert_eq!(
+            run::<Day25>("5764801\n17807724"),
+            vec![Answer::from(14897079)]
+        );
+        assert_eq!(
+            Day25::solve(2, "5764801\n17807724"),
+            Err(Error::UnknownPart { day: 25, part: 2 })
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use std::{borrow::Cow, io::BufRead};
+
+use crate::{Error, ParseError};
+
+/// Puts an input the way the generators expect it: no byte order mark, `\n` line endings
+/// and no trailing blank lines.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    if !input.contains('\r') {
+        return Cow::Borrowed(input.trim_end_matches('\n'));
+    }
+
+    let mut input = input.replace("\r\n", "\n");
+    input.truncate(input.trim_end_matches('\n').len());
+    Cow::Owned(input)
+}
+
+/// Parses `input` a line at a time. `parse` reports errors against the line it was given;
+/// they are moved to where that line sits in `input`.
+pub fn lines<T>(
+    input: &str,
+    mut parse: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse(line).map_err(|e| e.on_line(i + 1)))
+        .collect()
+}
+
+/// Like [`lines`], for blocks separated by blank lines. Any run of blank lines separates two
+/// blocks, as in [`read_blocks`].
+pub fn blocks<T>(
+    input: &str,
+    mut parse: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    let mut out = vec![];
+    // the line number and byte offset the current block starts at
+    let mut block = None;
+    let mut offset = 0;
+
+    for (i, line) in input.split('\n').enumerate() {
+        if line.is_empty() {
+            if let Some((start, from)) = block.take() {
+                out.push(parse(&input[from..offset - 1]).map_err(|e| e.on_line(start))?);
+            }
+        } else if block.is_none() {
+            block = Some((i + 1, offset));
+        }
+        offset += line.len() + 1;
+    }
+
+    if let Some((start, from)) = block {
+        out.push(parse(&input[from..]).map_err(|e| e.on_line(start))?);
+    }
+
+    Ok(out)
+}
+
+/// Calls `f` with the number and text of each line, reusing one buffer throughout.
+fn each_line(
+    mut reader: impl BufRead,
+    mut f: impl FnMut(usize, &str) -> Result<(), ParseError>,
+) -> Result<(), Error> {
+    let mut buf = String::new();
+    let mut number = 0;
+    loop {
+        buf.clear();
+        if reader
+            .read_line(&mut buf)
+            .map_err(|e| Error::Io(e.to_string()))?
+            == 0
+        {
+            return Ok(());
+        }
+
+        number += 1;
+        let line = if number == 1 {
+            buf.strip_prefix('\u{feff}').unwrap_or(&buf)
+        } else {
+            &buf
+        };
+        let line = match line.strip_suffix('\n') {
+            Some(line) => line.strip_suffix('\r').unwrap_or(line),
+            None => line,
+        };
+        f(number, line)?;
+    }
+}
+
+/// [`lines`] for a reader, holding no more than one line in memory besides the results.
+/// Trailing blank lines are skipped, as they are before the generators see an input.
+pub fn read_lines<T>(
+    reader: impl BufRead,
+    mut parse: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, Error> {
+    let mut out = vec![];
+    // blank lines that may yet turn out to be trailing
+    let mut blank = vec![];
+
+    each_line(reader, |number, line| {
+        if line.is_empty() {
+            blank.push(number);
+            return Ok(());
+        }
+
+        for number in blank.drain(..) {
+            out.push(parse("").map_err(|e| e.on_line(number))?);
+        }
+        out.push(parse(line).map_err(|e| e.on_line(number))?);
+        Ok(())
+    })?;
+
+    Ok(out)
+}
+
+/// [`blocks`] for a reader, holding no more than one block in memory besides the results.
+/// Any run of blank lines separates two blocks.
+pub fn read_blocks<T>(
+    reader: impl BufRead,
+    mut parse: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, Error> {
+    let mut out = vec![];
+    let mut block = String::new();
+    let mut start = 0;
+
+    each_line(reader, |number, line| {
+        if line.is_empty() {
+            if !block.is_empty() {
+                out.push(parse(&block).map_err(|e| e.on_line(start))?);
+                block.clear();
+            }
+        } else {
+            if block.is_empty() {
+                start = number;
+            } else {
+                block.push('\n');
+            }
+            block.push_str(line);
+        }
+        Ok(())
+    })?;
+
+    if !block.is_empty() {
+        out.push(parse(&block).map_err(|e| e.on_line(start))?);
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::parse_at;
+
+    fn number(line: &str) -> Result<u32, ParseError> {
+        parse_at(line, line, "a number")
+    }
+
+    fn total(block: &str) -> Result<u32, ParseError> {
+        lines(block, number).map(|numbers| numbers.iter().sum())
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("a\nb\n\n"), "a\nb");
+        assert_eq!(normalize("\u{feff}a\r\n\r\nb\r\n\r\n"), "a\n\nb");
+        assert_eq!(normalize("a\rb"), "a\rb");
+        assert!(matches!(normalize("a\n"), Cow::Borrowed("a")));
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(lines("1\n2\n3", number), Ok(vec![1, 2, 3]));
+        assert_eq!(
+            lines("1\n2\nx", number),
+            Err(ParseError::new(3, 1, "a number"))
+        );
+
+        assert_eq!(read_lines(&b"1\r\n2\n3\n\n"[..], number), Ok(vec![1, 2, 3]));
+        assert_eq!(
+            read_lines("\u{feff}1\r\n2\r\n".as_bytes(), number),
+            Ok(vec![1, 2])
+        );
+        assert_eq!(
+            read_lines(&b"1\n\n3\n"[..], number),
+            Err(Error::Parse(ParseError::new(2, 1, "a number")))
+        );
+    }
+
+    #[test]
+    fn test_blocks() {
+        assert_eq!(blocks("1\n2\n\n3", total), Ok(vec![3, 3]));
+        assert_eq!(read_blocks(&b"1\n2\n\n\n3\n"[..], total), Ok(vec![3, 3]));
+
+        // both take any run of blank lines, leading and trailing ones too, as one separator
+        let spaced = "\n1\n2\n\n\n\n3\n\n\n4\n\n";
+        assert_eq!(blocks(spaced, total), Ok(vec![3, 3, 4]));
+        assert_eq!(read_blocks(spaced.as_bytes(), total), Ok(vec![3, 3, 4]));
+        let spaced = "1\n\n\n\nx";
+        let err = ParseError::new(5, 1, "a number");
+        assert_eq!(blocks(spaced, total), Err(err.clone()));
+        assert_eq!(
+            read_blocks(spaced.as_bytes(), total),
+            Err(Error::Parse(err))
+        );
+
+        let err = ParseError::new(5, 1, "a number");
+        assert_eq!(blocks("1\n2\n\n3\nx", total), Err(err.clone()));
+        assert_eq!(
+            read_blocks(&b"1\n2\n\n3\nx"[..], total),
+            Err(Error::Parse(err))
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use std::{
+    fmt::Display,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, RwLock,
+    },
+};
+
+/// Something a solver noticed along the way: the day, what it was doing, and what it saw.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub day: u8,
+    pub phase: &'static str,
+    pub fields: Vec<(&'static str, String)>,
+}
+
+impl Event {
+    /// Looks up a field's value, as it was `Debug` formatted.
+    pub fn field(&self, key: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "day{} {}", self.day, self.phase)?;
+        for (key, value) in &self.fields {
+            write!(f, " {}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Where events go once [`install`]ed. Solvers may run on several threads at once.
+pub trait Sink: Send + Sync {
+    fn event(&self, event: &Event);
+}
+
+impl<F: Fn(&Event) + Send + Sync> Sink for F {
+    fn event(&self, event: &Event) {
+        self(event)
+    }
+}
+
+impl<S: Sink + ?Sized> Sink for Arc<S> {
+    fn event(&self, event: &Event) {
+        (**self).event(event)
+    }
+}
+
+/// Prints each event on its own line to stderr.
+pub struct Stderr;
+
+impl Sink for Stderr {
+    fn event(&self, event: &Event) {
+        eprintln!("{}", event);
+    }
+}
+
+/// Keeps every event, for looking at afterwards.
+#[derive(Debug, Default)]
+pub struct Recorder {
+    events: Mutex<Vec<Event>>,
+}
+
+impl Recorder {
+    /// The events recorded so far, leaving none behind.
+    pub fn take(&self) -> Vec<Event> {
+        std::mem::take(&mut self.events.lock().unwrap())
+    }
+}
+
+impl Sink for Recorder {
+    fn event(&self, event: &Event) {
+        self.events.lock().unwrap().push(event.clone());
+    }
+}
+
+static SINK: RwLock<Option<Box<dyn Sink>>> = RwLock::new(None);
+// checked before anything is formatted, so a build with tracing but no sink stays cheap
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Sends every event from now on to `sink`, returning the one it replaces.
+pub fn install(sink: impl Sink + 'static) -> Option<Box<dyn Sink>> {
+    let old = SINK.write().unwrap().replace(Box::new(sink));
+    ENABLED.store(true, Ordering::Release);
+    old
+}
+
+/// Stops tracing, returning the sink events were going to.
+pub fn uninstall() -> Option<Box<dyn Sink>> {
+    ENABLED.store(false, Ordering::Release);
+    SINK.write().unwrap().take()
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Acquire)
+}
+
+pub fn emit(event: Event) {
+    if let Some(sink) = SINK.read().unwrap().as_ref() {
+        sink.event(&event);
+    }
+}
+
+/// Emits an [`Event`] for `day` and `phase` to the installed sink, with each `key = value`
+/// (or a bare variable, keyed by its name) `Debug` formatted. Without the `trace` feature
+/// this expands to nothing, and the values are never evaluated.
+macro_rules! trace {
+    (@value $key:ident $value:expr) => {
+        $value
+    };
+    (@value $key:ident) => {
+        $key
+    };
+    ($day:expr, $phase:expr $(, $key:ident $(= $value:expr)?)* $(,)?) => {
+        #[cfg(feature = "trace")]
+        {
+            if $crate::trace::enabled() {
+                $crate::trace::emit($crate::trace::Event {
+                    day: $day,
+                    phase: $phase,
+                    fields: vec![$((
+                        stringify!($key),
+                        format!("{:?}", trace!(@value $key $($value)?)),
+                    )),*],
+                });
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_event() {
+        let event = Event {
+            day: 22,
+            phase: "round",
+            fields: vec![("p1", "[9, 2]".to_owned()), ("p2", "[5]".to_owned())],
+        };
+        assert_eq!(event.to_string(), "day22 round p1=[9, 2] p2=[5]");
+        assert_eq!(event.field("p2"), Some("[5]"));
+        assert_eq!(event.field("p3"), None);
+    }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn test_trace() {
+        let recorder = Arc::new(Recorder::default());
+        install(recorder.clone());
+        crate::solve(10, 1, "16\n10\n15\n5\n1\n11\n7\n19\n6\n12\n4").unwrap();
+        crate::solve(17, 1, ".#.\n..#\n###").unwrap();
+        crate::solve(18, 2, "1 + (2 * 3)\n2 * 3 + 4").unwrap();
+        uninstall();
+
+        // other tests may be solving at the same time, so look only for these
+        let events = recorder.take();
+        assert!(events
+            .iter()
+            .any(|e| e.to_string() == "day10 jumps ones=7 twos=0 threes=5"));
+        assert!(events
+            .iter()
+            .any(|e| e.to_string() == "day17 cycle generation=1 active=11"));
+        assert!(events
+            .iter()
+            .any(|e| e.to_string() == r#"day18 evaluate expression="1 + (2 * 3)" value=7"#));
+        assert!(events
+            .iter()
+            .any(|e| e.to_string() == r#"day18 evaluate expression="2 * 3 + 4" value=14"#));
+        assert!(!events.iter().any(|e| e.day == 18 && e.phase == "token"));
+    }
+}
@@ -0,0 +1,249 @@
+use std::{
+    fmt::Display,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::error::parse_at;
+use crate::{days, parts, solve, stream, Error, ParseError};
+
+/// Where cargo-aoc keeps the puzzle input for `day`, relative to `dir`.
+pub fn input_path(dir: &Path, day: u8) -> PathBuf {
+    dir.join("2020").join(format!("day{}.txt", day))
+}
+
+/// FNV-1a over the normalized input, so line endings and trailing newlines don't matter.
+pub fn hash_input(input: &str) -> u64 {
+    stream::normalize(input)
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+/// One line of the answers file: `day part hash answer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub day: u8,
+    pub part: u8,
+    pub hash: u64,
+    pub answer: String,
+}
+
+impl Display for Expected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {:016x} {}",
+            self.day, self.part, self.hash, self.answer
+        )
+    }
+}
+
+/// Parses an answers file. Blank lines and lines starting with `#` are skipped.
+pub fn parse_answers(input: &str) -> Result<Vec<Expected>, ParseError> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut fields = line.splitn(4, ' ');
+            let mut next = |expected: &str| {
+                fields
+                    .next()
+                    .filter(|field| !field.is_empty())
+                    .ok_or_else(|| ParseError::at(input, &line[line.len()..], expected))
+            };
+
+            let day = next("a day")?;
+            let part = next("a part")?;
+            let hash = next("an input hash")?;
+            let answer = next("an answer")?;
+
+            Ok(Expected {
+                day: parse_at(input, day, "a day")?,
+                part: parse_at(input, part, "a part")?,
+                hash: u64::from_str_radix(hash, 16)
+                    .map_err(|_| ParseError::at(input, hash, "a hexadecimal input hash"))?,
+                answer: answer.to_owned(),
+            })
+        })
+        .collect()
+}
+
+pub fn format_answers(answers: &[Expected]) -> String {
+    answers.iter().map(|a| format!("{}\n", a)).collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Match,
+    Mismatch {
+        expected: String,
+        actual: String,
+    },
+    /// The stored input no longer hashes to what the answer was recorded against.
+    InputChanged {
+        expected: u64,
+        actual: u64,
+    },
+    MissingInput(String),
+    Failed(Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub outcome: Outcome,
+}
+
+impl Check {
+    pub fn passed(&self) -> bool {
+        self.outcome == Outcome::Match
+    }
+}
+
+impl Display for Check {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "day {} part {}: ", self.day, self.part)?;
+        match &self.outcome {
+            Outcome::Match => write!(f, "ok"),
+            Outcome::Mismatch { expected, actual } => {
+                write!(f, "expected {}, got {}", expected, actual)
+            }
+            Outcome::InputChanged { expected, actual } => write!(
+                f,
+                "input hash is {:016x}, answer was recorded for {:016x}",
+                actual, expected
+            ),
+            Outcome::MissingInput(err) => write!(f, "missing input: {}", err),
+            Outcome::Failed(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+/// Checks one expected answer against `input`.
+pub fn check(expected: &Expected, input: &str) -> Check {
+    let hash = hash_input(input);
+    let outcome = if hash != expected.hash {
+        Outcome::InputChanged {
+            expected: expected.hash,
+            actual: hash,
+        }
+    } else {
+        match solve(expected.day, expected.part, input) {
+            Ok(answer) if answer.to_string() == expected.answer => Outcome::Match,
+            Ok(answer) => Outcome::Mismatch {
+                expected: expected.answer.clone(),
+                actual: answer.to_string(),
+            },
+            Err(err) => Outcome::Failed(err),
+        }
+    };
+
+    Check {
+        day: expected.day,
+        part: expected.part,
+        outcome,
+    }
+}
+
+/// Re-runs every expected answer against the inputs stored under `inputs`.
+pub fn verify(answers: &[Expected], inputs: &Path) -> Vec<Check> {
+    answers
+        .iter()
+        .map(
+            |expected| match fs::read_to_string(input_path(inputs, expected.day)) {
+                Ok(input) => check(expected, &input),
+                Err(err) => Check {
+                    day: expected.day,
+                    part: expected.part,
+                    outcome: Outcome::MissingInput(err.to_string()),
+                },
+            },
+        )
+        .collect()
+}
+
+/// Solves every day that has an input under `inputs` and records the answers.
+/// Days without an input are skipped.
+pub fn record(inputs: &Path) -> Result<Vec<Expected>, Error> {
+    let mut answers = vec![];
+    for day in days() {
+        let input = match fs::read_to_string(input_path(inputs, day)) {
+            Ok(input) => input,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(Error::Io(err.to_string())),
+        };
+
+        for &part in parts(day) {
+            answers.push(Expected {
+                day,
+                part,
+                hash: hash_input(&input),
+                answer: solve(day, part, &input)?.to_string(),
+            });
+        }
+    }
+
+    Ok(answers)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const DAY1: &str = "1721\n979\n366\n299\n675\n1456";
+
+    #[test]
+    fn test_answers_file() {
+        let answers = vec![
+            Expected {
+                day: 1,
+                part: 1,
+                hash: hash_input(DAY1),
+                answer: "514579".to_owned(),
+            },
+            Expected {
+                day: 8,
+                part: 2,
+                hash: 0xff,
+                answer: "index: 9, acc: 8".to_owned(),
+            },
+        ];
+        let text = format_answers(&answers);
+        assert_eq!(
+            parse_answers(&format!("# day part hash answer\n{}", text)),
+            Ok(answers)
+        );
+
+        let err = parse_answers("1 1 00ff 5\n1 2 zz 6").unwrap_err();
+        assert_eq!(err, ParseError::new(2, 5, "a hexadecimal input hash"));
+        assert_eq!(parse_answers("1 1").unwrap_err().line, 1);
+    }
+
+    #[test]
+    fn test_check() {
+        let mut expected = Expected {
+            day: 1,
+            part: 1,
+            hash: hash_input(DAY1),
+            answer: "514579".to_owned(),
+        };
+        assert!(check(&expected, &format!("{}\n", DAY1)).passed());
+
+        expected.answer = "1".to_owned();
+        assert_eq!(
+            check(&expected, DAY1).outcome,
+            Outcome::Mismatch {
+                expected: "1".to_owned(),
+                actual: "514579".to_owned()
+            }
+        );
+
+        assert!(matches!(
+            check(&expected, "1721\n979").outcome,
+            Outcome::InputChanged { .. }
+        ));
+    }
+}